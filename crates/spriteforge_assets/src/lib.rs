@@ -182,6 +182,15 @@ pub struct TilesheetMetadata {
     pub padding: u32,
     pub tile_count: usize,
     pub tiles: Vec<TileMetadata>,
+    /// Tile indices of each animation, in frame order.
+    #[serde(default)]
+    pub frame_groups: Vec<Vec<usize>>,
+    /// Tile indices of each tree's growth stages, from sapling to old, grouped by seed.
+    #[serde(default)]
+    pub growth_groups: Vec<Vec<usize>>,
+    /// Growth stage of full-grown trees in a sheet with growth stages.
+    #[serde(default)]
+    pub mature_stage: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -195,6 +204,52 @@ pub struct TileMetadata {
     pub height: u32,
     pub seed: u64,
     pub transition_mask: Option<u8>,
+    #[serde(default)]
+    pub cliff_mask: Option<u8>,
+    #[serde(default)]
+    pub rise_direction: Option<u8>,
+    #[serde(default)]
+    pub size_class: Option<String>,
+    #[serde(default)]
+    pub decoration: Option<String>,
+    /// Ground extent in pixels (width, height) of a decoration or building, centered on
+    /// `anchor`.
+    #[serde(default)]
+    pub footprint: Option<[u32; 2]>,
+    /// Pixel within the sprite where a decoration touches the ground, or the center of a
+    /// building's footprint.
+    #[serde(default)]
+    pub anchor: Option<[u32; 2]>,
+    #[serde(default)]
+    pub connection_mask: Option<u8>,
+    /// Edges where a bridge piece meets the shore.
+    #[serde(default)]
+    pub landing_mask: Option<u8>,
+    #[serde(default)]
+    pub material: Option<String>,
+    #[serde(default)]
+    pub moisture: Option<String>,
+    #[serde(default)]
+    pub crop: Option<String>,
+    /// Crop or tree growth stage; advance growth by swapping to the next stage of the same crop
+    /// or tree seed.
+    #[serde(default)]
+    pub stage: Option<u32>,
+    #[serde(default)]
+    pub roof: Option<String>,
+    /// Map tiles (along x, along y) covered by a multi-tile sprite such as a building.
+    #[serde(default)]
+    pub footprint_tiles: Option<[u32; 2]>,
+    /// Tree season; tiles of one tree in different seasons share `seed` and shape.
+    #[serde(default)]
+    pub season: Option<String>,
+    /// Wind sway frame; the frames of one tree are listed in `frame_groups`.
+    #[serde(default)]
+    pub frame: Option<u32>,
+    /// Quarter turns the camera has orbited clockwise for this view of a tree; a map viewed
+    /// after the same turns uses the tiles with matching `rotation`.
+    #[serde(default)]
+    pub rotation: Option<u32>,
 }
 
 pub fn load_tilesheet_metadata(path: &Path) -> Result<TilesheetMetadata, String> {
@@ -221,6 +276,61 @@ pub fn all_transition_masks() -> Vec<u8> {
     masks.into_iter().filter(|mask| *mask != 0).collect()
 }
 
+/// Cliff variants only depend on which edge neighbors sit lower, so corners are ignored.
+pub fn all_cliff_masks() -> Vec<u8> {
+    (0..=EDGE_MASK).collect()
+}
+
+/// Fence and wall variants connect to any combination of edge neighbors.
+pub fn all_connection_masks() -> Vec<u8> {
+    (0..=EDGE_MASK).collect()
+}
+
+/// Pier pieces: lone platforms, ends, straights, corners, T junctions and crossings.
+pub fn all_pier_masks() -> Vec<u8> {
+    (0..=EDGE_MASK).collect()
+}
+
+/// Bridge pieces as (deck axis, landing edges): open spans, end caps and single-tile spans.
+pub fn all_bridge_pieces() -> Vec<(u8, u8)> {
+    let mut pieces = Vec::new();
+    for (axis, low, high) in [(EDGE_N | EDGE_S, EDGE_S, EDGE_N), (EDGE_E | EDGE_W, EDGE_W, EDGE_E)] {
+        for landing in [0, low, high, low | high] {
+            pieces.push((axis, landing));
+        }
+    }
+    pieces
+}
+
+/// Ramp rise directions: straight ramps climb toward an edge, outer corner ramps toward a corner
+/// and inner corner ramps toward two adjacent edges.
+pub fn all_ramp_directions() -> Vec<u8> {
+    vec![
+        EDGE_N,
+        EDGE_E,
+        EDGE_S,
+        EDGE_W,
+        CORNER_NE,
+        CORNER_SE,
+        CORNER_SW,
+        CORNER_NW,
+        EDGE_N | EDGE_E,
+        EDGE_S | EDGE_E,
+        EDGE_S | EDGE_W,
+        EDGE_N | EDGE_W,
+    ]
+}
+
+/// Builds a cliff mask from neighbor elevations given in N, E, S, W order.
+pub fn cliff_mask_for_heights(height: i32, neighbors: [i32; 4]) -> u8 {
+    let edges = [EDGE_N, EDGE_E, EDGE_S, EDGE_W];
+    edges
+        .iter()
+        .zip(neighbors)
+        .filter(|(_, neighbor)| *neighbor < height)
+        .fold(0, |mask, (edge, _)| mask | edge)
+}
+
 pub fn angles_for_mask(mask: u8) -> Vec<f32> {
     let mask = normalize_mask(mask);
     let mut angles = Vec::new();
@@ -282,6 +392,13 @@ mod tests {
         assert_eq!(masks.len(), 46);
     }
 
+    #[test]
+    fn cliff_mask_marks_lower_neighbors() {
+        assert_eq!(all_cliff_masks().len(), 16);
+        assert_eq!(cliff_mask_for_heights(1, [1, 1, 1, 1]), 0);
+        assert_eq!(cliff_mask_for_heights(2, [2, 0, 1, 3]), EDGE_E | EDGE_S);
+    }
+
     fn assert_close(actual: f32, expected: f32) {
         assert!(
             (actual - expected).abs() <= EPS,
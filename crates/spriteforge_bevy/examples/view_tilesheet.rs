@@ -36,14 +36,37 @@ const WATER_IMAGE: &str = "out/tilesheet/water.png";
 const WATER_META: &str = "out/tilesheet/water.json";
 const WATER_TRANSITION_IMAGE: &str = "out/tilesheet/water_transition.png";
 const WATER_TRANSITION_META: &str = "out/tilesheet/water_transition.json";
+const SAND_IMAGE: &str = "out/tilesheet/sand.png";
+const SAND_META: &str = "out/tilesheet/sand.json";
+const SAND_TRANSITION_IMAGE: &str = "out/tilesheet/sand_transition.png";
+const SAND_TRANSITION_META: &str = "out/tilesheet/sand_transition.json";
 const WATER_MASK_IMAGE: &str = "out/tilesheet/water_mask.png";
 const WATER_TRANSITION_MASK_IMAGE: &str = "out/tilesheet/water_transition_mask.png";
 const TREE_IMAGE: &str = "out/tilesheet/tree.png";
 const TREE_META: &str = "out/tilesheet/tree.json";
 const TREE_MASK_IMAGE: &str = "out/tilesheet/tree_mask.png";
+const TREE_SHADOW_IMAGE: &str = "out/tilesheet/tree_shadow.png";
 const BUSH_IMAGE: &str = "out/tilesheet/bush.png";
 const BUSH_META: &str = "out/tilesheet/bush.json";
 const BUSH_MASK_IMAGE: &str = "out/tilesheet/bush_mask.png";
+const BUSH_SHADOW_IMAGE: &str = "out/tilesheet/bush_shadow.png";
+const ROCK_IMAGE: &str = "out/tilesheet/rock.png";
+const ROCK_META: &str = "out/tilesheet/rock.json";
+const ROCK_MASK_IMAGE: &str = "out/tilesheet/rock_mask.png";
+const DECORATION_IMAGE: &str = "out/tilesheet/decoration.png";
+const DECORATION_META: &str = "out/tilesheet/decoration.json";
+const FENCE_IMAGE: &str = "out/tilesheet/fence.png";
+const FENCE_META: &str = "out/tilesheet/fence.json";
+const WALL_IMAGE: &str = "out/tilesheet/wall.png";
+const WALL_META: &str = "out/tilesheet/wall.json";
+const BRIDGE_IMAGE: &str = "out/tilesheet/bridge.png";
+const BRIDGE_META: &str = "out/tilesheet/bridge.json";
+const PIER_IMAGE: &str = "out/tilesheet/pier.png";
+const PIER_META: &str = "out/tilesheet/pier.json";
+const FARMLAND_IMAGE: &str = "out/tilesheet/farmland.png";
+const FARMLAND_META: &str = "out/tilesheet/farmland.json";
+const CROP_IMAGE: &str = "out/tilesheet/crop.png";
+const CROP_META: &str = "out/tilesheet/crop.json";
 const MAP_WIDTH: u32 = 64;
 const MAP_HEIGHT: u32 = 64;
 const MAP_LAYOUT_CONFIG: &str = "assets/map_layouts/rural_fork.json";
@@ -66,14 +89,37 @@ struct TilesheetPaths {
     water_meta: PathBuf,
     water_transition_image: PathBuf,
     water_transition_meta: PathBuf,
+    sand_image: PathBuf,
+    sand_meta: PathBuf,
+    sand_transition_image: PathBuf,
+    sand_transition_meta: PathBuf,
     water_mask_image: PathBuf,
     water_transition_mask_image: PathBuf,
     tree_image: PathBuf,
     tree_meta: PathBuf,
     tree_mask_image: PathBuf,
+    tree_shadow_image: PathBuf,
     bush_image: PathBuf,
     bush_meta: PathBuf,
     bush_mask_image: PathBuf,
+    bush_shadow_image: PathBuf,
+    rock_image: PathBuf,
+    rock_meta: PathBuf,
+    rock_mask_image: PathBuf,
+    decoration_image: PathBuf,
+    decoration_meta: PathBuf,
+    fence_image: PathBuf,
+    fence_meta: PathBuf,
+    wall_image: PathBuf,
+    wall_meta: PathBuf,
+    bridge_image: PathBuf,
+    bridge_meta: PathBuf,
+    pier_image: PathBuf,
+    pier_meta: PathBuf,
+    farmland_image: PathBuf,
+    farmland_meta: PathBuf,
+    crop_image: PathBuf,
+    crop_meta: PathBuf,
 }
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone, Default)]
@@ -271,14 +317,37 @@ fn main() {
             water_meta: workspace_root.join(WATER_META),
             water_transition_image: PathBuf::from(WATER_TRANSITION_IMAGE),
             water_transition_meta: workspace_root.join(WATER_TRANSITION_META),
+            sand_image: PathBuf::from(SAND_IMAGE),
+            sand_meta: workspace_root.join(SAND_META),
+            sand_transition_image: PathBuf::from(SAND_TRANSITION_IMAGE),
+            sand_transition_meta: workspace_root.join(SAND_TRANSITION_META),
             water_mask_image: PathBuf::from(WATER_MASK_IMAGE),
             water_transition_mask_image: PathBuf::from(WATER_TRANSITION_MASK_IMAGE),
             tree_image: PathBuf::from(TREE_IMAGE),
             tree_meta: workspace_root.join(TREE_META),
             tree_mask_image: PathBuf::from(TREE_MASK_IMAGE),
+            tree_shadow_image: PathBuf::from(TREE_SHADOW_IMAGE),
             bush_image: PathBuf::from(BUSH_IMAGE),
             bush_meta: workspace_root.join(BUSH_META),
             bush_mask_image: PathBuf::from(BUSH_MASK_IMAGE),
+            bush_shadow_image: PathBuf::from(BUSH_SHADOW_IMAGE),
+            rock_image: PathBuf::from(ROCK_IMAGE),
+            rock_meta: workspace_root.join(ROCK_META),
+            rock_mask_image: PathBuf::from(ROCK_MASK_IMAGE),
+            decoration_image: PathBuf::from(DECORATION_IMAGE),
+            decoration_meta: workspace_root.join(DECORATION_META),
+            fence_image: PathBuf::from(FENCE_IMAGE),
+            fence_meta: workspace_root.join(FENCE_META),
+            wall_image: PathBuf::from(WALL_IMAGE),
+            wall_meta: workspace_root.join(WALL_META),
+            bridge_image: PathBuf::from(BRIDGE_IMAGE),
+            bridge_meta: workspace_root.join(BRIDGE_META),
+            pier_image: PathBuf::from(PIER_IMAGE),
+            pier_meta: workspace_root.join(PIER_META),
+            farmland_image: PathBuf::from(FARMLAND_IMAGE),
+            farmland_meta: workspace_root.join(FARMLAND_META),
+            crop_image: PathBuf::from(CROP_IMAGE),
+            crop_meta: workspace_root.join(CROP_META),
         })
         .add_systems(Startup, setup)
         .add_systems(
@@ -373,6 +442,20 @@ fn setup(
             return;
         }
     };
+    let sand_meta = match load_tilesheet_metadata(&paths.sand_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load sand metadata: {err}");
+            return;
+        }
+    };
+    let sand_transition_meta = match load_tilesheet_metadata(&paths.sand_transition_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load sand transition metadata: {err}");
+            return;
+        }
+    };
     let tree_meta = match load_tilesheet_metadata(&paths.tree_meta) {
         Ok(data) => data,
         Err(err) => {
@@ -387,6 +470,62 @@ fn setup(
             return;
         }
     };
+    let rock_meta = match load_tilesheet_metadata(&paths.rock_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load rock metadata: {err}");
+            return;
+        }
+    };
+    let decoration_meta = match load_tilesheet_metadata(&paths.decoration_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load decoration metadata: {err}");
+            return;
+        }
+    };
+    let fence_meta = match load_tilesheet_metadata(&paths.fence_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load fence metadata: {err}");
+            return;
+        }
+    };
+    let wall_meta = match load_tilesheet_metadata(&paths.wall_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load wall metadata: {err}");
+            return;
+        }
+    };
+    let bridge_meta = match load_tilesheet_metadata(&paths.bridge_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load bridge metadata: {err}");
+            return;
+        }
+    };
+    let pier_meta = match load_tilesheet_metadata(&paths.pier_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load pier metadata: {err}");
+            return;
+        }
+    };
+    let farmland_meta = match load_tilesheet_metadata(&paths.farmland_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load farmland metadata: {err}");
+            return;
+        }
+    };
+    let crop_meta = match load_tilesheet_metadata(&paths.crop_meta) {
+        Ok(data) => data,
+        Err(err) => {
+            eprintln!("Failed to load crop metadata: {err}");
+            return;
+        }
+    };
 
     let grass_texture: Handle<Image> =
         asset_server.load(paths.grass_image.to_string_lossy().to_string());
@@ -402,10 +541,30 @@ fn setup(
         asset_server.load(paths.water_image.to_string_lossy().to_string());
     let water_transition_texture: Handle<Image> =
         asset_server.load(paths.water_transition_image.to_string_lossy().to_string());
+    let sand_texture: Handle<Image> =
+        asset_server.load(paths.sand_image.to_string_lossy().to_string());
+    let sand_transition_texture: Handle<Image> =
+        asset_server.load(paths.sand_transition_image.to_string_lossy().to_string());
     let tree_texture: Handle<Image> =
         asset_server.load(paths.tree_image.to_string_lossy().to_string());
     let bush_texture: Handle<Image> =
         asset_server.load(paths.bush_image.to_string_lossy().to_string());
+    let rock_texture: Handle<Image> =
+        asset_server.load(paths.rock_image.to_string_lossy().to_string());
+    let decoration_texture: Handle<Image> =
+        asset_server.load(paths.decoration_image.to_string_lossy().to_string());
+    let fence_texture: Handle<Image> =
+        asset_server.load(paths.fence_image.to_string_lossy().to_string());
+    let wall_texture: Handle<Image> =
+        asset_server.load(paths.wall_image.to_string_lossy().to_string());
+    let bridge_texture: Handle<Image> =
+        asset_server.load(paths.bridge_image.to_string_lossy().to_string());
+    let pier_texture: Handle<Image> =
+        asset_server.load(paths.pier_image.to_string_lossy().to_string());
+    let farmland_texture: Handle<Image> =
+        asset_server.load(paths.farmland_image.to_string_lossy().to_string());
+    let crop_texture: Handle<Image> =
+        asset_server.load(paths.crop_image.to_string_lossy().to_string());
     let water_mask_texture: Handle<Image> =
         asset_server.load(paths.water_mask_image.to_string_lossy().to_string());
     let water_transition_mask_texture: Handle<Image> =
@@ -414,6 +573,12 @@ fn setup(
         asset_server.load(paths.tree_mask_image.to_string_lossy().to_string());
     let bush_mask_texture: Handle<Image> =
         asset_server.load(paths.bush_mask_image.to_string_lossy().to_string());
+    let tree_shadow_texture: Handle<Image> =
+        asset_server.load(paths.tree_shadow_image.to_string_lossy().to_string());
+    let bush_shadow_texture: Handle<Image> =
+        asset_server.load(paths.bush_shadow_image.to_string_lossy().to_string());
+    let rock_mask_texture: Handle<Image> =
+        asset_server.load(paths.rock_mask_image.to_string_lossy().to_string());
 
     let (map_width, map_height) = (MAP_WIDTH, MAP_HEIGHT);
     let map_size = TilemapSize {
@@ -439,6 +604,36 @@ fn setup(
         x: bush_sprite_width,
         y: bush_sprite_height,
     };
+    let rock_sprite_width = rock_meta.sprite_width.unwrap_or(256) as f32;
+    let rock_sprite_height = rock_meta.sprite_height.unwrap_or(256) as f32;
+    let rock_tile_size = TilemapTileSize {
+        x: rock_sprite_width,
+        y: rock_sprite_height,
+    };
+    let decoration_tile_size = TilemapTileSize {
+        x: decoration_meta.sprite_width.unwrap_or(64) as f32,
+        y: decoration_meta.sprite_height.unwrap_or(64) as f32,
+    };
+    let fence_tile_size = TilemapTileSize {
+        x: fence_meta.sprite_width.unwrap_or(256) as f32,
+        y: fence_meta.sprite_height.unwrap_or(256) as f32,
+    };
+    let wall_tile_size = TilemapTileSize {
+        x: wall_meta.sprite_width.unwrap_or(256) as f32,
+        y: wall_meta.sprite_height.unwrap_or(256) as f32,
+    };
+    let bridge_tile_size = TilemapTileSize {
+        x: bridge_meta.sprite_width.unwrap_or(256) as f32,
+        y: bridge_meta.sprite_height.unwrap_or(256) as f32,
+    };
+    let pier_tile_size = TilemapTileSize {
+        x: pier_meta.sprite_width.unwrap_or(256) as f32,
+        y: pier_meta.sprite_height.unwrap_or(256) as f32,
+    };
+    let crop_tile_size = TilemapTileSize {
+        x: crop_meta.sprite_width.unwrap_or(256) as f32,
+        y: crop_meta.sprite_height.unwrap_or(384) as f32,
+    };
     let grid_size = TilemapGridSize {
         x: sprite_width,
         y: sprite_width * 0.5,
@@ -465,6 +660,10 @@ fn setup(
         normal_texture: bush_mask_texture,
         params: tree_light_params(TimeOfDay::Dawn),
     });
+    let rock_material = tree_materials.add(TreeLightMaterial {
+        normal_texture: rock_mask_texture,
+        params: tree_light_params(TimeOfDay::Dawn),
+    });
     let hover_outline_texture =
         images.add(create_outline_image(sprite_width as u32, [255, 255, 255, 255], 2));
     let selected_outline_texture =
@@ -524,6 +723,32 @@ fn setup(
         0.3,
         Some(LayerMaterial::Water(water_transition_material.clone())),
     );
+    push_layer(LayerKind::Sand, sand_meta, sand_texture, tile_size, 0.05, None);
+    push_layer(
+        LayerKind::SandTransition,
+        sand_transition_meta,
+        sand_transition_texture,
+        tile_size,
+        0.1,
+        None,
+    );
+    // Shadows sit on the ground, under everything standing on it.
+    push_layer(
+        LayerKind::TreeShadows,
+        tree_meta.clone(),
+        tree_shadow_texture,
+        tree_tile_size,
+        1.1,
+        None,
+    );
+    push_layer(
+        LayerKind::BushShadows,
+        bush_meta.clone(),
+        bush_shadow_texture,
+        bush_tile_size,
+        1.1,
+        None,
+    );
     push_layer(
         LayerKind::Trees,
         tree_meta,
@@ -540,10 +765,46 @@ fn setup(
         1.5,
         Some(LayerMaterial::Tree(bush_material.clone())),
     );
+    push_layer(
+        LayerKind::Rocks,
+        rock_meta,
+        rock_texture,
+        rock_tile_size,
+        1.4,
+        Some(LayerMaterial::Tree(rock_material.clone())),
+    );
+    push_layer(
+        LayerKind::Decorations,
+        decoration_meta,
+        decoration_texture,
+        decoration_tile_size,
+        1.3,
+        None,
+    );
+    push_layer(LayerKind::Fences, fence_meta, fence_texture, fence_tile_size, 1.35, None);
+    push_layer(LayerKind::Walls, wall_meta, wall_texture, wall_tile_size, 1.45, None);
+    push_layer(
+        LayerKind::Bridges,
+        bridge_meta,
+        bridge_texture,
+        bridge_tile_size,
+        1.25,
+        None,
+    );
+    push_layer(LayerKind::Piers, pier_meta, pier_texture, pier_tile_size, 1.25, None);
+    push_layer(
+        LayerKind::Farmland,
+        farmland_meta,
+        farmland_texture,
+        tile_size,
+        0.6,
+        None,
+    );
+    push_layer(LayerKind::Crops, crop_meta, crop_texture, crop_tile_size, 1.32, None);
     let assets = MapAssets {
         layout_config,
         layers: LayerCatalog { layers, order },
-        tree_materials: vec![tree_material, bush_material],
+        tree_materials: vec![tree_material, bush_material, rock_material],
         hover_outline_texture,
         selected_outline_texture,
         map_size,
@@ -643,6 +904,12 @@ fn spawn_map(
         let mut transform =
             get_tilemap_center_transform(&assets.map_size, &assets.grid_size, &map_type, 0.0);
         transform.translation.y += (layer_assets.tile_size.y - layer_assets.tile_size.x) * 0.5;
+        // Decorations are smaller than a tile, so move their anchor onto the tile's ground center.
+        if let Some([_, anchor_y]) = layer_assets.meta.tiles.first().and_then(|tile| tile.anchor) {
+            transform.translation.y += anchor_y as f32 - layer_assets.tile_size.y
+                + layer_assets.tile_size.x * 0.5
+                - assets.grid_size.x * 0.25;
+        }
         transform.translation.z = layer_assets.z;
         let storage = layer_storages
             .remove(kind)
@@ -928,6 +1195,10 @@ fn update_selected_tile_ui(
         Some(BaseTile::Dirt) => "Dirt",
         Some(BaseTile::Path) => "Path",
         Some(BaseTile::Water) => "Water",
+        Some(BaseTile::Sand) => "Sand",
+        Some(BaseTile::Bridge) => "Bridge",
+        Some(BaseTile::Pier) => "Pier",
+        Some(BaseTile::Farmland) => "Farmland",
         None => "Unknown",
     };
     let mut lines = vec![
@@ -966,6 +1237,17 @@ fn environment_kind_label(kind: map_raster::EnvironmentKind) -> &'static str {
     match kind {
         map_raster::EnvironmentKind::Tree => "Tree",
         map_raster::EnvironmentKind::Bush => "Bush",
+        map_raster::EnvironmentKind::Rock => "Rock",
+        map_raster::EnvironmentKind::Flower => "Flower",
+        map_raster::EnvironmentKind::Mushroom => "Mushroom",
+        map_raster::EnvironmentKind::Tuft => "Grass tuft",
+        map_raster::EnvironmentKind::Reed => "Reed",
+        map_raster::EnvironmentKind::Log => "Fallen log",
+        map_raster::EnvironmentKind::Fence => "Fence",
+        map_raster::EnvironmentKind::Wall => "Wall",
+        map_raster::EnvironmentKind::Wheat => "Wheat",
+        map_raster::EnvironmentKind::Corn => "Corn",
+        map_raster::EnvironmentKind::Cabbage => "Cabbage",
     }
 }
 
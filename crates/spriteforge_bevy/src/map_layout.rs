@@ -5,6 +5,7 @@ use std::path::Path;
 const PATH_RADIUS: i32 = 1;
 const CONNECTOR_RADIUS: i32 = 0;
 const DOCK_CHANCE: f64 = 0.25;
+const FIELD_CHANCE: f64 = 0.35;
 
 #[derive(Clone, Copy, Debug)]
 pub struct PathSegment {
@@ -18,6 +19,7 @@ pub struct PathSegment {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AreaType {
     Dock,
+    Field,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -25,6 +27,9 @@ pub struct MapArea {
     pub center_x: i32,
     pub center_y: i32,
     pub radius: i32,
+    pub major: bool,
+    /// Whether the area is ringed with a wall (major) or a fence (minor).
+    pub enclosed: bool,
     pub area_type: Option<AreaType>,
 }
 
@@ -48,6 +53,8 @@ pub struct MapAreaConfig {
     #[serde(default)]
     pub major: bool,
     #[serde(default)]
+    pub enclosed: bool,
+    #[serde(default)]
     pub connect_to: Option<ConnectorTargetConfig>,
 }
 
@@ -279,6 +286,8 @@ fn build_areas(
                         None
                     } else if rng.gen_bool(DOCK_CHANCE) {
                         Some(AreaType::Dock)
+                    } else if rng.gen_bool(FIELD_CHANCE) {
+                        Some(AreaType::Field)
                     } else {
                         None
                     };
@@ -286,6 +295,8 @@ fn build_areas(
                         center_x: cx,
                         center_y: cy,
                         radius,
+                        major: config.major,
+                        enclosed: config.enclosed,
                         area_type,
                     });
                     mark_circle_occupancy(cx, cy, radius, width, height, &mut area_occupied);
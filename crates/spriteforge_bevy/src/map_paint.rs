@@ -18,6 +18,10 @@ pub enum BaseTile {
     Dirt,
     Path,
     Water,
+    Sand,
+    Bridge,
+    Pier,
+    Farmland,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -29,8 +33,21 @@ pub enum LayerKind {
     Transition,
     Water,
     WaterTransition,
+    Sand,
+    SandTransition,
     Trees,
     Bushes,
+    /// Drop shadows share the tree and bush sheet layouts, so they reuse their tile indices.
+    TreeShadows,
+    BushShadows,
+    Rocks,
+    Decorations,
+    Fences,
+    Walls,
+    Bridges,
+    Piers,
+    Farmland,
+    Crops,
 }
 
 #[derive(Debug, Clone)]
@@ -60,8 +77,18 @@ where
     let water_meta = meta_for(LayerKind::Water);
     let water_transition_meta = meta_for(LayerKind::WaterTransition);
     let transition_meta = meta_for(LayerKind::Transition);
+    let sand_meta = meta_for(LayerKind::Sand);
+    let sand_transition_meta = meta_for(LayerKind::SandTransition);
     let tree_meta = meta_for(LayerKind::Trees);
     let bush_meta = meta_for(LayerKind::Bushes);
+    let rock_meta = meta_for(LayerKind::Rocks);
+    let decoration_meta = meta_for(LayerKind::Decorations);
+    let fence_meta = meta_for(LayerKind::Fences);
+    let wall_meta = meta_for(LayerKind::Walls);
+    let bridge_meta = meta_for(LayerKind::Bridges);
+    let pier_meta = meta_for(LayerKind::Piers);
+    let farmland_meta = meta_for(LayerKind::Farmland);
+    let crop_meta = meta_for(LayerKind::Crops);
 
     let mut grass = vec![None; base_tiles.len()];
     let mut dirt = vec![None; base_tiles.len()];
@@ -70,12 +97,65 @@ where
     let mut water = vec![None; base_tiles.len()];
     let mut water_transition = vec![None; base_tiles.len()];
     let mut transition = vec![None; base_tiles.len()];
+    let mut sand = vec![None; base_tiles.len()];
+    let mut sand_transition = vec![None; base_tiles.len()];
     let mut trees = vec![None; base_tiles.len()];
     let mut bushes = vec![None; base_tiles.len()];
+    let mut rocks = vec![None; base_tiles.len()];
+    let mut decorations = vec![None; base_tiles.len()];
+    let mut fences = vec![None; base_tiles.len()];
+    let mut walls = vec![None; base_tiles.len()];
+    let mut bridges = vec![None; base_tiles.len()];
+    let mut piers = vec![None; base_tiles.len()];
+    let mut farmland = vec![None; base_tiles.len()];
+    let mut crops = vec![None; base_tiles.len()];
 
     let transition_lookup = build_transition_lookup(transition_meta);
     let path_transition_lookup = build_transition_lookup(path_transition_meta);
     let water_transition_lookup = build_transition_lookup(water_transition_meta);
+    let sand_transition_lookup = build_transition_lookup(sand_transition_meta);
+    let decoration_lookup = build_decoration_lookup(decoration_meta);
+    let fence_lookup = build_connection_lookup(fence_meta);
+    let wall_lookup = build_connection_lookup(wall_meta);
+    let pier_lookup = build_connection_lookup(pier_meta);
+    let farmland_lookup = build_farmland_lookup(farmland_meta);
+    let crop_lookup = build_crop_lookup(crop_meta);
+    // Furrows run the same way across the map and every field shows the same growth stage.
+    let furrow_axis = if rng.gen_bool(0.5) {
+        EDGE_N | EDGE_S
+    } else {
+        EDGE_E | EDGE_W
+    };
+    let crop_stage = crop_meta
+        .tiles
+        .iter()
+        .filter_map(|tile| tile.stage)
+        .max()
+        .map(|last| rng.gen_range(0..=last));
+    // Every tree and bush on a map shares one season.
+    let mut seasons: Vec<&String> = tree_meta
+        .tiles
+        .iter()
+        .filter_map(|tile| tile.season.as_ref())
+        .collect();
+    seasons.sort();
+    seasons.dedup();
+    let season = if seasons.is_empty() {
+        None
+    } else {
+        Some(seasons[rng.gen_range(0..seasons.len())].clone())
+    };
+    let tree_choices = season_tiles(tree_meta, season.as_deref());
+    let bush_choices = season_tiles(bush_meta, season.as_deref());
+    let bridge_lookups = build_bridge_lookup(bridge_meta);
+    // One material per map keeps every crossing in the same style.
+    let mut materials: Vec<&String> = bridge_lookups.keys().collect();
+    materials.sort();
+    let bridge_lookup = if materials.is_empty() {
+        None
+    } else {
+        Some(&bridge_lookups[materials[rng.gen_range(0..materials.len())]])
+    };
 
     for y in 0..height {
         for x in 0..width {
@@ -87,14 +167,19 @@ where
                         let index = pick_transition_index(mask, &transition_lookup, rng)
                             .unwrap_or_else(|| rng.gen_range(0..dirt_meta.tile_count) as u32);
                         transition[idx] = Some(index);
-                        let dirt_index = rng.gen_range(0..dirt_meta.tile_count) as u32;
-                        dirt[idx] = Some(dirt_index);
+                        if adjacent_sand_mask(x, y, width, height, base_tiles) != 0 {
+                            let sand_index = rng.gen_range(0..sand_meta.tile_count) as u32;
+                            sand[idx] = Some(sand_index);
+                        } else {
+                            let dirt_index = rng.gen_range(0..dirt_meta.tile_count) as u32;
+                            dirt[idx] = Some(dirt_index);
+                        }
                     } else {
                         let index = rng.gen_range(0..grass_meta.tile_count) as u32;
                         grass[idx] = Some(index);
                     }
                 }
-                BaseTile::Water => {
+                BaseTile::Water | BaseTile::Bridge | BaseTile::Pier => {
                     let mask = adjacent_non_water_mask(x, y, width, height, base_tiles);
                     if mask != 0 {
                         let index =
@@ -107,6 +192,45 @@ where
                         let index = rng.gen_range(0..water_meta.tile_count) as u32;
                         water[idx] = Some(index);
                     }
+                    if base_tiles[idx] == BaseTile::Bridge {
+                        let (connection, landing) =
+                            bridge_piece(x, y, width, height, base_tiles);
+                        bridges[idx] = bridge_lookup
+                            .and_then(|lookup| lookup.get(&(connection, landing)))
+                            .filter(|choices| !choices.is_empty())
+                            .map(|choices| choices[rng.gen_range(0..choices.len())]);
+                    }
+                    if base_tiles[idx] == BaseTile::Pier {
+                        let mask = adjacent_mask(x, y, width, height, base_tiles, |tile| {
+                            matches!(tile, BaseTile::Pier | BaseTile::Path)
+                        }) & EDGE_MASK;
+                        piers[idx] = pick_connection_index(mask, &pier_lookup, rng);
+                    }
+                }
+                BaseTile::Sand => {
+                    let mask = adjacent_water_mask(x, y, width, height, base_tiles);
+                    if mask != 0 {
+                        let index = pick_transition_index(mask, &sand_transition_lookup, rng)
+                            .unwrap_or_else(|| {
+                                rng.gen_range(0..sand_transition_meta.tile_count) as u32
+                            });
+                        sand_transition[idx] = Some(index);
+                    } else {
+                        let sand_index = rng.gen_range(0..sand_meta.tile_count) as u32;
+                        sand[idx] = Some(sand_index);
+                    }
+                }
+                BaseTile::Farmland => {
+                    // Soil next to water stays wet.
+                    let moisture = if adjacent_water_mask(x, y, width, height, base_tiles) != 0 {
+                        "wet"
+                    } else {
+                        "dry"
+                    };
+                    farmland[idx] = farmland_lookup
+                        .get(&(moisture.to_string(), furrow_axis))
+                        .filter(|choices| !choices.is_empty())
+                        .map(|choices| choices[rng.gen_range(0..choices.len())]);
                 }
                 BaseTile::Dirt => {
                     let dirt_index = rng.gen_range(0..dirt_meta.tile_count) as u32;
@@ -127,8 +251,13 @@ where
                 }
             }
             if water_transition[idx].is_some() && dirt[idx].is_none() {
-                let dirt_index = rng.gen_range(0..dirt_meta.tile_count) as u32;
-                dirt[idx] = Some(dirt_index);
+                if adjacent_sand_mask(x, y, width, height, base_tiles) != 0 {
+                    let sand_index = rng.gen_range(0..sand_meta.tile_count) as u32;
+                    sand[idx] = Some(sand_index);
+                } else {
+                    let dirt_index = rng.gen_range(0..dirt_meta.tile_count) as u32;
+                    dirt[idx] = Some(dirt_index);
+                }
             }
             if path_transition[idx].is_some() && dirt[idx].is_none() {
                 let dirt_index = rng.gen_range(0..dirt_meta.tile_count) as u32;
@@ -137,6 +266,13 @@ where
         }
     }
 
+    let mut occupancy = vec![None; base_tiles.len()];
+    for object in environment {
+        if object.x < width && object.y < height {
+            occupancy[(object.y * width + object.x) as usize] = Some(object.kind);
+        }
+    }
+
     for object in environment {
         if object.x >= width || object.y >= height {
             continue;
@@ -144,13 +280,50 @@ where
         let idx = (object.y * width + object.x) as usize;
         match object.kind {
             EnvironmentKind::Tree => {
-                let tree_index = rng.gen_range(0..tree_meta.tile_count) as u32;
+                let tree_index = tree_choices[rng.gen_range(0..tree_choices.len())];
                 trees[idx] = Some(tree_index);
             }
             EnvironmentKind::Bush => {
-                let bush_index = rng.gen_range(0..bush_meta.tile_count) as u32;
+                let bush_index = bush_choices[rng.gen_range(0..bush_choices.len())];
                 bushes[idx] = Some(bush_index);
             }
+            EnvironmentKind::Rock => {
+                let rock_index = rng.gen_range(0..rock_meta.tile_count) as u32;
+                rocks[idx] = Some(rock_index);
+            }
+            EnvironmentKind::Flower
+            | EnvironmentKind::Mushroom
+            | EnvironmentKind::Tuft
+            | EnvironmentKind::Reed
+            | EnvironmentKind::Log => {
+                let choices = decoration_name(object.kind)
+                    .and_then(|name| decoration_lookup.get(name))
+                    .filter(|choices| !choices.is_empty());
+                if let Some(choices) = choices {
+                    decorations[idx] = Some(choices[rng.gen_range(0..choices.len())]);
+                }
+            }
+            EnvironmentKind::Wheat | EnvironmentKind::Corn | EnvironmentKind::Cabbage => {
+                let choices = crop_name(object.kind)
+                    .zip(crop_stage)
+                    .and_then(|(name, stage)| crop_lookup.get(&(name.to_string(), stage)))
+                    .filter(|choices| !choices.is_empty());
+                if let Some(choices) = choices {
+                    crops[idx] = Some(choices[rng.gen_range(0..choices.len())]);
+                }
+            }
+            EnvironmentKind::Fence | EnvironmentKind::Wall => {
+                // Linear structures join every edge neighbor of the same kind.
+                let same_kind = |kind| kind == Some(object.kind);
+                let mask = adjacent_mask(object.x, object.y, width, height, &occupancy, same_kind)
+                    & EDGE_MASK;
+                let (lookup, layer) = if object.kind == EnvironmentKind::Fence {
+                    (&fence_lookup, &mut fences)
+                } else {
+                    (&wall_lookup, &mut walls)
+                };
+                layer[idx] = pick_connection_index(mask, lookup, rng);
+            }
         }
     }
 
@@ -162,8 +335,20 @@ where
     layers.insert(LayerKind::Water, water);
     layers.insert(LayerKind::WaterTransition, water_transition);
     layers.insert(LayerKind::Transition, transition);
+    layers.insert(LayerKind::Sand, sand);
+    layers.insert(LayerKind::SandTransition, sand_transition);
+    layers.insert(LayerKind::TreeShadows, trees.clone());
+    layers.insert(LayerKind::BushShadows, bushes.clone());
     layers.insert(LayerKind::Trees, trees);
     layers.insert(LayerKind::Bushes, bushes);
+    layers.insert(LayerKind::Rocks, rocks);
+    layers.insert(LayerKind::Decorations, decorations);
+    layers.insert(LayerKind::Fences, fences);
+    layers.insert(LayerKind::Walls, walls);
+    layers.insert(LayerKind::Bridges, bridges);
+    layers.insert(LayerKind::Piers, piers);
+    layers.insert(LayerKind::Farmland, farmland);
+    layers.insert(LayerKind::Crops, crops);
 
     RenderTileLayers {
         width,
@@ -179,7 +364,20 @@ fn adjacent_non_water_mask(
     height: u32,
     tiles: &[BaseTile],
 ) -> u8 {
-    adjacent_mask(x, y, width, height, tiles, |tile| tile != BaseTile::Water)
+    adjacent_mask(x, y, width, height, tiles, |tile| !is_water(tile))
+}
+
+fn adjacent_water_mask(x: u32, y: u32, width: u32, height: u32, tiles: &[BaseTile]) -> u8 {
+    adjacent_mask(x, y, width, height, tiles, is_water)
+}
+
+/// Bridges and piers stand over open water, so shores and transitions treat them as water.
+fn is_water(tile: BaseTile) -> bool {
+    matches!(tile, BaseTile::Water | BaseTile::Bridge | BaseTile::Pier)
+}
+
+fn adjacent_sand_mask(x: u32, y: u32, width: u32, height: u32, tiles: &[BaseTile]) -> u8 {
+    adjacent_mask(x, y, width, height, tiles, |tile| tile == BaseTile::Sand)
 }
 
 fn adjacent_non_grass_mask(
@@ -199,19 +397,39 @@ fn adjacent_non_path_mask(
     height: u32,
     tiles: &[BaseTile],
 ) -> u8 {
-    adjacent_mask(x, y, width, height, tiles, |tile| tile != BaseTile::Path)
+    adjacent_mask(x, y, width, height, tiles, |tile| {
+        !matches!(tile, BaseTile::Path | BaseTile::Bridge | BaseTile::Pier)
+    })
 }
 
-fn adjacent_mask<F>(
+/// Deck axis and landing edges for a bridge cell. The deck follows whichever grid axis has
+/// more path or bridge neighbors, and lands on the edges that do not continue as bridge.
+fn bridge_piece(x: u32, y: u32, width: u32, height: u32, tiles: &[BaseTile]) -> (u8, u8) {
+    let walkable = adjacent_mask(x, y, width, height, tiles, |tile| {
+        matches!(tile, BaseTile::Path | BaseTile::Bridge)
+    }) & EDGE_MASK;
+    let along_x = (walkable & (EDGE_E | EDGE_W)).count_ones();
+    let along_y = (walkable & (EDGE_N | EDGE_S)).count_ones();
+    let axis = if along_x > along_y {
+        EDGE_E | EDGE_W
+    } else {
+        EDGE_N | EDGE_S
+    };
+    let open = adjacent_mask(x, y, width, height, tiles, |tile| tile != BaseTile::Bridge);
+    (axis, open & axis)
+}
+
+fn adjacent_mask<T, F>(
     x: u32,
     y: u32,
     width: u32,
     height: u32,
-    tiles: &[BaseTile],
+    tiles: &[T],
     mut is_match: F,
 ) -> u8
 where
-    F: FnMut(BaseTile) -> bool,
+    T: Copy,
+    F: FnMut(T) -> bool,
 {
     let mut mask = 0u8;
 
@@ -266,6 +484,129 @@ fn build_transition_lookup(meta: &TilesheetMetadata) -> std::collections::HashMa
     map
 }
 
+fn build_connection_lookup(meta: &TilesheetMetadata) -> HashMap<u8, Vec<u32>> {
+    let mut map = HashMap::new();
+    for tile in &meta.tiles {
+        let Some(mask) = tile.connection_mask else {
+            continue;
+        };
+        map.entry(mask).or_insert_with(Vec::new).push(tile.index as u32);
+    }
+    map
+}
+
+/// Groups bridge tile indices by material, then by (deck axis, landing edges).
+fn build_bridge_lookup(meta: &TilesheetMetadata) -> HashMap<String, HashMap<(u8, u8), Vec<u32>>> {
+    let mut map: HashMap<String, HashMap<(u8, u8), Vec<u32>>> = HashMap::new();
+    for tile in &meta.tiles {
+        let (Some(axis), Some(material)) = (tile.connection_mask, tile.material.clone()) else {
+            continue;
+        };
+        map.entry(material)
+            .or_default()
+            .entry((axis, tile.landing_mask.unwrap_or(0)))
+            .or_default()
+            .push(tile.index as u32);
+    }
+    map
+}
+
+/// Groups farmland tile indices by moisture and furrow axis.
+fn build_farmland_lookup(meta: &TilesheetMetadata) -> HashMap<(String, u8), Vec<u32>> {
+    let mut map = HashMap::new();
+    for tile in &meta.tiles {
+        let (Some(moisture), Some(axis)) = (tile.moisture.clone(), tile.connection_mask) else {
+            continue;
+        };
+        map.entry((moisture, axis))
+            .or_insert_with(Vec::new)
+            .push(tile.index as u32);
+    }
+    map
+}
+
+/// Groups crop tile indices by crop and growth stage.
+fn build_crop_lookup(meta: &TilesheetMetadata) -> HashMap<(String, u32), Vec<u32>> {
+    let mut map = HashMap::new();
+    for tile in &meta.tiles {
+        let (Some(crop), Some(stage)) = (tile.crop.clone(), tile.stage) else {
+            continue;
+        };
+        map.entry((crop, stage))
+            .or_insert_with(Vec::new)
+            .push(tile.index as u32);
+    }
+    map
+}
+
+/// Tile indices showing a season. Animated sheets contribute the first frame of each tree,
+/// rotated sheets the unrotated view and growth sheets the mature stage. When no tile is in
+/// the season, every season of that view is used instead.
+fn season_tiles(meta: &TilesheetMetadata, season: Option<&str>) -> Vec<u32> {
+    let base_view: Vec<&TileMetadata> = meta
+        .tiles
+        .iter()
+        .filter(|tile| tile.frame.unwrap_or(0) == 0 && tile.rotation.unwrap_or(0) == 0)
+        .filter(|tile| match (tile.stage, meta.mature_stage) {
+            (Some(stage), Some(mature)) => stage == mature,
+            _ => true,
+        })
+        .collect();
+    let matching: Vec<u32> = base_view
+        .iter()
+        .filter(|tile| season.is_some() && tile.season.as_deref() == season)
+        .map(|tile| tile.index as u32)
+        .collect();
+    if !matching.is_empty() {
+        return matching;
+    }
+    if base_view.is_empty() {
+        return (0..meta.tile_count as u32).collect();
+    }
+    base_view.iter().map(|tile| tile.index as u32).collect()
+}
+
+/// Name of the crop tilesheet entries that render an environment kind.
+fn crop_name(kind: EnvironmentKind) -> Option<&'static str> {
+    match kind {
+        EnvironmentKind::Wheat => Some("wheat"),
+        EnvironmentKind::Corn => Some("corn"),
+        EnvironmentKind::Cabbage => Some("cabbage"),
+        _ => None,
+    }
+}
+
+/// Groups decoration tile indices by their `decoration` kind.
+fn build_decoration_lookup(meta: &TilesheetMetadata) -> HashMap<String, Vec<u32>> {
+    let mut map = HashMap::new();
+    for tile in &meta.tiles {
+        let Some(kind) = tile.decoration.clone() else {
+            continue;
+        };
+        map.entry(kind).or_insert_with(Vec::new).push(tile.index as u32);
+    }
+    map
+}
+
+/// Name of the decoration tilesheet entries that render an environment kind.
+fn decoration_name(kind: EnvironmentKind) -> Option<&'static str> {
+    match kind {
+        EnvironmentKind::Flower => Some("flower"),
+        EnvironmentKind::Mushroom => Some("mushroom"),
+        EnvironmentKind::Tuft => Some("tuft"),
+        EnvironmentKind::Reed => Some("reed"),
+        EnvironmentKind::Log => Some("log"),
+        EnvironmentKind::Tree
+        | EnvironmentKind::Bush
+        | EnvironmentKind::Rock
+        | EnvironmentKind::Fence
+        | EnvironmentKind::Wall
+        | EnvironmentKind::Wheat
+        | EnvironmentKind::Corn
+        | EnvironmentKind::Cabbage => None,
+    }
+}
+
 fn pick_transition_index<R: rand::Rng>(
     mask: u8,
     lookup: &std::collections::HashMap<u8, Vec<u32>>,
@@ -281,3 +622,132 @@ fn pick_transition_index<R: rand::Rng>(
     }
     Some(choices[rng.gen_range(0..choices.len())])
 }
+
+/// Picks a connection tile for an exact set of edges. Unlike transition masks, connection
+/// masks carry no corner bits, so they are looked up without normalizing.
+fn pick_connection_index<R: rand::Rng>(
+    mask: u8,
+    lookup: &HashMap<u8, Vec<u32>>,
+    rng: &mut R,
+) -> Option<u32> {
+    let choices = lookup.get(&(mask & EDGE_MASK))?;
+    if choices.is_empty() {
+        return None;
+    }
+    Some(choices[rng.gen_range(0..choices.len())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    /// A sheet of 64x64 tiles, each carrying the extra metadata `fields` gives it.
+    fn sheet(fields: Vec<serde_json::Value>) -> TilesheetMetadata {
+        let tiles: Vec<serde_json::Value> = fields
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut tile)| {
+                tile.as_object_mut().unwrap().extend(
+                    serde_json::json!({
+                        "index": index, "row": 0, "col": index, "x": 0, "y": 0,
+                        "width": 64, "height": 64, "seed": 1,
+                    })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+                );
+                tile
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "image": "sheet.png", "config": "sheet.config", "sprite_width": 64,
+            "sprite_height": 64, "columns": tiles.len(), "rows": 1, "padding": 0,
+            "tile_count": tiles.len(), "tiles": tiles,
+        }))
+        .unwrap()
+    }
+
+    /// One tile per connection mask, indexed by its mask.
+    fn connection_sheet() -> TilesheetMetadata {
+        sheet(
+            (0..=EDGE_MASK)
+                .map(|mask| serde_json::json!({ "connection_mask": mask }))
+                .collect(),
+        )
+    }
+
+    /// Paints `base_tiles` with a plain one-tile sheet for every layer but `connected`, which
+    /// gets a connection sheet.
+    fn paint(
+        base_tiles: &[BaseTile],
+        environment: &[EnvironmentObject],
+        width: u32,
+        height: u32,
+        connected: LayerKind,
+    ) -> Vec<Option<u32>> {
+        let plain = sheet(vec![serde_json::json!({})]);
+        let connections = connection_sheet();
+        let meta_for = |kind| if kind == connected { &connections } else { &plain };
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut layers =
+            build_render_layers(base_tiles, environment, width, height, meta_for, &mut rng);
+        layers.layers.remove(&connected).unwrap()
+    }
+
+    #[test]
+    fn fence_variants_follow_neighbouring_fences() {
+        // An L of fence: an end at (0,1), a straight run through (1,1) and a corner at (2,1)
+        // turning north to (2,2).
+        let fence_at = |x, y| EnvironmentObject {
+            kind: EnvironmentKind::Fence,
+            x,
+            y,
+        };
+        let environment = [fence_at(0, 1), fence_at(1, 1), fence_at(2, 1), fence_at(2, 2)];
+        let fences = paint(&[BaseTile::Grass; 9], &environment, 3, 3, LayerKind::Fences);
+        assert_eq!(fences[3], Some(EDGE_E as u32));
+        assert_eq!(fences[4], Some((EDGE_E | EDGE_W) as u32));
+        assert_eq!(fences[5], Some((EDGE_W | EDGE_N) as u32));
+        assert_eq!(fences[8], Some(EDGE_S as u32));
+        assert_eq!(fences[0], None);
+    }
+
+    #[test]
+    fn lone_and_crossing_pier_cells_get_their_own_pieces() {
+        let mut base_tiles = vec![BaseTile::Water; 9];
+        base_tiles[4] = BaseTile::Pier;
+        let piers = paint(&base_tiles, &[], 3, 3, LayerKind::Piers);
+        assert_eq!(piers[4], Some(0));
+
+        for idx in [1, 3, 5, 7] {
+            base_tiles[idx] = BaseTile::Pier;
+        }
+        let piers = paint(&base_tiles, &[], 3, 3, LayerKind::Piers);
+        assert_eq!(piers[4], Some(EDGE_MASK as u32));
+        assert_eq!(piers[1], Some(EDGE_N as u32));
+    }
+
+    #[test]
+    fn off_season_sheets_keep_the_unrotated_first_frame() {
+        let mut tiles = Vec::new();
+        for rotation in 0..4 {
+            for frame in 0..2 {
+                tiles.push(serde_json::json!({
+                    "index": tiles.len(), "row": 0, "col": tiles.len(), "x": 0, "y": 0,
+                    "width": 64, "height": 64, "seed": 1,
+                    "season": "summer", "rotation": rotation, "frame": frame,
+                }));
+            }
+        }
+        let meta: TilesheetMetadata = serde_json::from_value(serde_json::json!({
+            "image": "bush.png", "config": "bush.config", "sprite_width": 64,
+            "sprite_height": 64, "columns": 8, "rows": 1, "padding": 0,
+            "tile_count": tiles.len(), "tiles": tiles,
+        }))
+        .unwrap();
+        assert_eq!(season_tiles(&meta, Some("summer")), vec![0]);
+        assert_eq!(season_tiles(&meta, Some("autumn")), vec![0]);
+    }
+}
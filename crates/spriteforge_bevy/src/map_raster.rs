@@ -1,4 +1,4 @@
-use crate::map_layout::{AreaType, MapLayout, PathSegment};
+use crate::map_layout::{AreaType, MapArea, MapLayout, PathSegment};
 use crate::BaseTile;
 use rand::Rng;
 
@@ -6,6 +6,17 @@ use rand::Rng;
 pub enum EnvironmentKind {
     Tree,
     Bush,
+    Rock,
+    Flower,
+    Mushroom,
+    Tuft,
+    Reed,
+    Log,
+    Fence,
+    Wall,
+    Wheat,
+    Corn,
+    Cabbage,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,46 +55,223 @@ pub fn rasterize_layout<R: Rng>(
             continue;
         }
         fill_water_circle(width, height, area.center_x, area.center_y, area.radius, &mut cells);
+        add_pier_head(width, height, area, &mut cells);
     }
     for segment in &skeleton.water_paths {
         rasterize_water_segment(width, height, segment, &mut cells);
     }
-    let environment = place_environment_objects(width, height, &cells, rng);
+    apply_beaches(width, height, &mut cells);
+    for area in &skeleton.areas {
+        if area.area_type == Some(AreaType::Field) {
+            till_field(width, height, area, &mut cells);
+        }
+    }
+    let mut placed = place_area_enclosures(width, height, &skeleton.areas, &cells);
+    placed.extend(plant_fields(width, height, &skeleton.areas, &cells, rng));
+    let environment = place_environment_objects(width, height, &cells, placed, rng);
     RasterizedMap {
         base_tiles: cells,
         environment,
     }
 }
 
+/// Turns the grass inside a field area into farmland, leaving the rim for its fence.
+fn till_field(width: u32, height: u32, area: &MapArea, cells: &mut [BaseTile]) {
+    let inside = |x: i32, y: i32| {
+        let (dx, dy) = (x - area.center_x, y - area.center_y);
+        dx * dx + dy * dy <= area.radius * area.radius
+    };
+    for y in (area.center_y - area.radius)..=(area.center_y + area.radius) {
+        for x in (area.center_x - area.radius)..=(area.center_x + area.radius) {
+            if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+                continue;
+            }
+            let interior = (-1..=1).all(|dy| (-1..=1).all(|dx| inside(x + dx, y + dy)));
+            let idx = (y as u32 * width + x as u32) as usize;
+            if interior && cells[idx] == BaseTile::Grass {
+                cells[idx] = BaseTile::Farmland;
+            }
+        }
+    }
+}
+
+/// Sows one crop per field on all of its farmland.
+fn plant_fields<R: Rng>(
+    width: u32,
+    height: u32,
+    areas: &[MapArea],
+    tiles: &[BaseTile],
+    rng: &mut R,
+) -> Vec<EnvironmentObject> {
+    let mut objects = Vec::new();
+    for area in areas {
+        if area.area_type != Some(AreaType::Field) {
+            continue;
+        }
+        let kind = match rng.gen_range(0..3) {
+            0 => EnvironmentKind::Wheat,
+            1 => EnvironmentKind::Corn,
+            _ => EnvironmentKind::Cabbage,
+        };
+        for y in (area.center_y - area.radius).max(0)..=(area.center_y + area.radius) {
+            for x in (area.center_x - area.radius).max(0)..=(area.center_x + area.radius) {
+                let (x, y) = (x as u32, y as u32);
+                if x >= width || y >= height {
+                    continue;
+                }
+                if tiles[(y * width + x) as usize] == BaseTile::Farmland {
+                    objects.push(EnvironmentObject { kind, x, y });
+                }
+            }
+        }
+    }
+    objects
+}
+
+/// Rings fields and `enclosed` areas with a wall (major areas) or a fence (minor areas). Only
+/// grass cells are fenced, so paths and water running into an area leave gates open.
+fn place_area_enclosures(
+    width: u32,
+    height: u32,
+    areas: &[MapArea],
+    tiles: &[BaseTile],
+) -> Vec<EnvironmentObject> {
+    let mut objects = Vec::new();
+    for area in areas {
+        let fenced = match area.area_type {
+            Some(AreaType::Dock) => false,
+            Some(AreaType::Field) => true,
+            None => area.enclosed,
+        };
+        if !fenced {
+            continue;
+        }
+        let kind = if area.major {
+            EnvironmentKind::Wall
+        } else {
+            EnvironmentKind::Fence
+        };
+        let inside = |x: i32, y: i32| {
+            let (dx, dy) = (x - area.center_x, y - area.center_y);
+            dx * dx + dy * dy <= area.radius * area.radius
+        };
+        for y in (area.center_y - area.radius)..=(area.center_y + area.radius) {
+            for x in (area.center_x - area.radius)..=(area.center_x + area.radius) {
+                if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 || !inside(x, y) {
+                    continue;
+                }
+                // Using all eight neighbors keeps the ring connected through its edges.
+                let on_border = (-1..=1).any(|dy| (-1..=1).any(|dx| !inside(x + dx, y + dy)));
+                let idx = (y as u32 * width + x as u32) as usize;
+                if on_border && tiles[idx] == BaseTile::Grass {
+                    objects.push(EnvironmentObject {
+                        kind,
+                        x: x as u32,
+                        y: y as u32,
+                    });
+                }
+            }
+        }
+    }
+    objects
+}
+
 fn place_environment_objects<R: Rng>(
     width: u32,
     height: u32,
     tiles: &[BaseTile],
+    placed: Vec<EnvironmentObject>,
     rng: &mut R,
 ) -> Vec<EnvironmentObject> {
     let environment_density = 0.08;
     let bush_chance = 0.35;
-    let mut objects = Vec::new();
+    let rock_density = 0.02;
+    let mut occupied = vec![None; tiles.len()];
+    for object in &placed {
+        occupied[(object.y * width + object.x) as usize] = Some(object.kind);
+    }
+    let mut objects = placed;
     for y in 0..height {
         for x in 0..width {
             let idx = (y * width + x) as usize;
-            if tiles[idx] != BaseTile::Grass {
+            if occupied[idx].is_some() {
                 continue;
             }
-            if !rng.gen_bool(environment_density) {
+            let tile = tiles[idx];
+            if tile == BaseTile::Grass && rng.gen_bool(environment_density) {
+                let kind = if rng.gen_bool(bush_chance) {
+                    EnvironmentKind::Bush
+                } else {
+                    EnvironmentKind::Tree
+                };
+                objects.push(EnvironmentObject { kind, x, y });
+                occupied[idx] = Some(kind);
                 continue;
             }
-            let kind = if rng.gen_bool(bush_chance) {
-                EnvironmentKind::Bush
-            } else {
-                EnvironmentKind::Tree
+            // Rocks also show up on bare ground and beaches, never on paths or water.
+            let rock_ground = matches!(tile, BaseTile::Grass | BaseTile::Dirt | BaseTile::Sand);
+            if rock_ground && rng.gen_bool(rock_density) {
+                objects.push(EnvironmentObject {
+                    kind: EnvironmentKind::Rock,
+                    x,
+                    y,
+                });
+                occupied[idx] = Some(EnvironmentKind::Rock);
+            }
+        }
+    }
+
+    // Decorations fill the remaining cells once trees are known, so mushrooms and logs can
+    // gather in their shade.
+    for y in 0..height {
+        for x in 0..width {
+            let idx = (y * width + x) as usize;
+            if occupied[idx].is_some() {
+                continue;
+            }
+            let tile = tiles[idx];
+            let near = |kind: EnvironmentKind, grid: &[Option<EnvironmentKind>]| {
+                neighbors(x, y, width, height).any(|n| grid[n] == Some(kind))
+            };
+            let near_water = neighbors(x, y, width, height).any(|n| tiles[n] == BaseTile::Water);
+            let kind = match tile {
+                BaseTile::Grass | BaseTile::Sand if near_water => {
+                    rng.gen_bool(0.35).then_some(EnvironmentKind::Reed)
+                }
+                BaseTile::Grass if near(EnvironmentKind::Tree, &occupied) => {
+                    match rng.gen_range(0..20) {
+                        0..=2 => Some(EnvironmentKind::Mushroom),
+                        3 => Some(EnvironmentKind::Log),
+                        _ => None,
+                    }
+                }
+                BaseTile::Grass => match rng.gen_range(0..100) {
+                    0..=3 => Some(EnvironmentKind::Flower),
+                    4..=9 => Some(EnvironmentKind::Tuft),
+                    _ => None,
+                },
+                BaseTile::Dirt => rng.gen_bool(0.03).then_some(EnvironmentKind::Tuft),
+                _ => None,
             };
-            objects.push(EnvironmentObject { kind, x, y });
+            if let Some(kind) = kind {
+                objects.push(EnvironmentObject { kind, x, y });
+                occupied[idx] = Some(kind);
+            }
         }
     }
     objects
 }
 
+/// Indices of the eight cells around (x, y) that lie inside the map.
+fn neighbors(x: u32, y: u32, width: u32, height: u32) -> impl Iterator<Item = usize> {
+    (-1..=1_i32).flat_map(move |dy| {
+        (-1..=1_i32).filter_map(move |dx| {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            let inside = nx >= 0 && ny >= 0 && (nx as u32) < width && (ny as u32) < height;
+            (inside && (dx, dy) != (0, 0)).then(|| (ny as u32 * width + nx as u32) as usize)
+        })
+    })
+}
 
 fn rasterize_segment(width: u32, height: u32, segment: &PathSegment, cells: &mut [BaseTile]) {
     let dx = (segment.end_x - segment.start_x).signum();
@@ -128,10 +316,38 @@ fn rasterize_water_segment(width: u32, height: u32, segment: &PathSegment, cells
                     continue;
                 }
                 let idx = (ny * width + nx) as usize;
-                if matches!(cells[idx], BaseTile::Dirt | BaseTile::Path) {
-                    continue;
-                }
-                cells[idx] = BaseTile::Water;
+                // Paths crossing the water stay walkable on a bridge; their dirt shoulders
+                // are flooded so the bridge spans open water.
+                cells[idx] = match cells[idx] {
+                    BaseTile::Path | BaseTile::Bridge => BaseTile::Bridge,
+                    BaseTile::Pier => BaseTile::Pier,
+                    _ => BaseTile::Water,
+                };
+            }
+        }
+    }
+}
+
+fn apply_beaches(width: u32, height: u32, cells: &mut [BaseTile]) {
+    let source = cells.to_vec();
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            let idx = (y as u32 * width + x as u32) as usize;
+            if source[idx] != BaseTile::Grass {
+                continue;
+            }
+            let touches_water = (-1..=1).any(|dy| {
+                (-1..=1).any(|dx| {
+                    let (nx, ny) = (x + dx, y + dy);
+                    nx >= 0
+                        && ny >= 0
+                        && (nx as u32) < width
+                        && (ny as u32) < height
+                        && source[(ny as u32 * width + nx as u32) as usize] == BaseTile::Water
+                })
+            });
+            if touches_water {
+                cells[idx] = BaseTile::Sand;
             }
         }
     }
@@ -163,10 +379,37 @@ fn fill_water_circle(
                 continue;
             }
             let idx = (y_u * width + x_u) as usize;
-            if matches!(cells[idx], BaseTile::Dirt | BaseTile::Path) {
-                continue;
-            }
-            cells[idx] = BaseTile::Water;
+            // The path leading into a dock pond continues as a pier.
+            cells[idx] = if cells[idx] == BaseTile::Path {
+                BaseTile::Pier
+            } else {
+                BaseTile::Water
+            };
+        }
+    }
+}
+
+/// Widens the end of a pier at the pond center into a T so boats can tie up on both sides.
+fn add_pier_head(width: u32, height: u32, area: &MapArea, cells: &mut [BaseTile]) {
+    let (x, y) = (area.center_x, area.center_y);
+    let tile_at = |x: i32, y: i32, cells: &[BaseTile]| {
+        (x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height)
+            .then(|| cells[(y as u32 * width + x as u32) as usize])
+    };
+    if tile_at(x, y, cells) != Some(BaseTile::Pier) {
+        return;
+    }
+    let runs_along_x = [(x - 1, y), (x + 1, y)]
+        .into_iter()
+        .any(|(nx, ny)| tile_at(nx, ny, cells) == Some(BaseTile::Pier));
+    let head = if runs_along_x {
+        [(x, y - 1), (x, y + 1)]
+    } else {
+        [(x - 1, y), (x + 1, y)]
+    };
+    for (hx, hy) in head {
+        if tile_at(hx, hy, cells) == Some(BaseTile::Water) {
+            cells[(hy as u32 * width + hx as u32) as usize] = BaseTile::Pier;
         }
     }
 }
@@ -214,12 +457,14 @@ mod tests {
                     x: 1.0 / 6.0,
                     y: 1.0 / 4.0,
                     major: false,
+                    enclosed: true,
                     connect_to: None,
                 },
                 MapAreaConfig {
                     x: 3.0 / 4.0,
                     y: 3.0 / 4.0,
                     major: true,
+                    enclosed: true,
                     connect_to: None,
                 },
             ],
@@ -266,6 +511,132 @@ mod tests {
         );
     }
 
+    #[test]
+    fn water_is_never_next_to_grass() {
+        let width = 64;
+        let height = 64;
+        let mut rng = StdRng::seed_from_u64(7);
+        let layout = crate::map_layout::generate_map_layout(
+            width,
+            height,
+            &mut rng,
+            &sample_layout_config(),
+        );
+        let raster = rasterize_layout(width, height, &layout, &mut rng);
+        assert!(raster.base_tiles.contains(&BaseTile::Water));
+        let tile_at = |x: i32, y: i32| {
+            if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+                return None;
+            }
+            Some(raster.base_tiles[(y as u32 * width + x as u32) as usize])
+        };
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                if tile_at(x, y) != Some(BaseTile::Water) {
+                    continue;
+                }
+                for (nx, ny) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+                    assert_ne!(
+                        tile_at(nx, ny),
+                        Some(BaseTile::Grass),
+                        "grass touches water at {nx},{ny}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reeds_only_grow_next_to_water() {
+        let width = 64;
+        let height = 64;
+        let mut rng = StdRng::seed_from_u64(7);
+        let layout = crate::map_layout::generate_map_layout(
+            width,
+            height,
+            &mut rng,
+            &sample_layout_config(),
+        );
+        let raster = rasterize_layout(width, height, &layout, &mut rng);
+        let reeds: Vec<_> = raster
+            .environment
+            .iter()
+            .filter(|object| object.kind == EnvironmentKind::Reed)
+            .collect();
+        assert!(!reeds.is_empty());
+        for reed in reeds {
+            assert!(
+                neighbors(reed.x, reed.y, width, height)
+                    .any(|n| raster.base_tiles[n] == BaseTile::Water),
+                "reed at {},{} is not next to water",
+                reed.x,
+                reed.y
+            );
+        }
+    }
+
+    #[test]
+    fn enclosures_only_stand_on_free_grass() {
+        let width = 64;
+        let height = 64;
+        let mut rng = StdRng::seed_from_u64(1337);
+        let layout = crate::map_layout::generate_map_layout(
+            width,
+            height,
+            &mut rng,
+            &sample_layout_config(),
+        );
+        let raster = rasterize_layout(width, height, &layout, &mut rng);
+        let mut seen = std::collections::HashSet::new();
+        for object in &raster.environment {
+            assert!(
+                seen.insert((object.x, object.y)),
+                "two objects at {},{}",
+                object.x,
+                object.y
+            );
+            if matches!(object.kind, EnvironmentKind::Fence | EnvironmentKind::Wall) {
+                let idx = (object.y * width + object.x) as usize;
+                assert_eq!(raster.base_tiles[idx], BaseTile::Grass);
+            }
+        }
+        assert!(raster
+            .environment
+            .iter()
+            .any(|object| matches!(object.kind, EnvironmentKind::Fence | EnvironmentKind::Wall)));
+    }
+
+    #[test]
+    fn only_enclosed_areas_are_ringed() {
+        let width = 32;
+        let height = 32;
+        let mut layout = MapLayout {
+            paths: Vec::new(),
+            areas: vec![MapArea {
+                center_x: 16,
+                center_y: 16,
+                radius: 5,
+                major: false,
+                enclosed: false,
+                area_type: None,
+            }],
+            water_paths: Vec::new(),
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        let raster = rasterize_layout(width, height, &layout, &mut rng);
+        assert!(!raster
+            .environment
+            .iter()
+            .any(|object| object.kind == EnvironmentKind::Fence));
+
+        layout.areas[0].enclosed = true;
+        let raster = rasterize_layout(width, height, &layout, &mut rng);
+        assert!(raster
+            .environment
+            .iter()
+            .any(|object| object.kind == EnvironmentKind::Fence));
+    }
+
     #[test]
     fn skeleton_total_length_reasonable() {
         let width = 64;
@@ -289,5 +660,117 @@ mod tests {
             "skeleton length too large: {total_length}"
         );
     }
-}
 
+    #[test]
+    fn paths_cross_water_on_bridges() {
+        let width = 32;
+        let height = 32;
+        let layout = MapLayout {
+            paths: vec![PathSegment {
+                start_x: 0,
+                start_y: 10,
+                end_x: 31,
+                end_y: 10,
+                radius: 1,
+            }],
+            areas: Vec::new(),
+            water_paths: vec![PathSegment {
+                start_x: 16,
+                start_y: 0,
+                end_x: 16,
+                end_y: 31,
+                radius: 2,
+            }],
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        let raster = rasterize_layout(width, height, &layout, &mut rng);
+        let tile_at = |x: u32, y: u32| raster.base_tiles[(y * width + x) as usize];
+        for x in 0..width {
+            for y in [10, 11] {
+                assert!(
+                    matches!(tile_at(x, y), BaseTile::Path | BaseTile::Bridge),
+                    "path cut at {x},{y}"
+                );
+            }
+        }
+        assert_eq!(tile_at(16, 10), BaseTile::Bridge);
+        assert_eq!(tile_at(16, 9), BaseTile::Water);
+        assert_eq!(tile_at(0, 10), BaseTile::Path);
+    }
+
+    #[test]
+    fn dock_paths_continue_as_piers() {
+        let width = 32;
+        let height = 32;
+        let layout = MapLayout {
+            paths: vec![PathSegment {
+                start_x: 16,
+                start_y: 16,
+                end_x: 16,
+                end_y: 31,
+                radius: 0,
+            }],
+            areas: vec![MapArea {
+                center_x: 16,
+                center_y: 16,
+                radius: 5,
+                major: false,
+                enclosed: false,
+                area_type: Some(AreaType::Dock),
+            }],
+            water_paths: Vec::new(),
+        };
+        let mut rng = StdRng::seed_from_u64(5);
+        let raster = rasterize_layout(width, height, &layout, &mut rng);
+        let tile_at = |x: u32, y: u32| raster.base_tiles[(y * width + x) as usize];
+        for y in 16..=21 {
+            assert_eq!(tile_at(16, y), BaseTile::Pier, "no pier at 16,{y}");
+        }
+        assert_eq!(tile_at(15, 16), BaseTile::Pier);
+        assert_eq!(tile_at(17, 16), BaseTile::Pier);
+        assert_eq!(tile_at(15, 18), BaseTile::Water);
+        assert_eq!(tile_at(16, 22), BaseTile::Path);
+    }
+
+    #[test]
+    fn fields_are_tilled_fenced_and_sown() {
+        let width = 32;
+        let height = 32;
+        let layout = MapLayout {
+            paths: Vec::new(),
+            areas: vec![MapArea {
+                center_x: 16,
+                center_y: 16,
+                radius: 5,
+                major: false,
+                enclosed: false,
+                area_type: Some(AreaType::Field),
+            }],
+            water_paths: Vec::new(),
+        };
+        let mut rng = StdRng::seed_from_u64(11);
+        let raster = rasterize_layout(width, height, &layout, &mut rng);
+        let farmland = raster
+            .base_tiles
+            .iter()
+            .filter(|tile| **tile == BaseTile::Farmland)
+            .count();
+        assert!(farmland > 0);
+        let crops: Vec<_> = raster
+            .environment
+            .iter()
+            .filter(|object| {
+                matches!(
+                    object.kind,
+                    EnvironmentKind::Wheat | EnvironmentKind::Corn | EnvironmentKind::Cabbage
+                )
+            })
+            .collect();
+        assert_eq!(crops.len(), farmland);
+        assert!(crops.iter().all(|crop| crop.kind == crops[0].kind));
+        assert!(raster
+            .environment
+            .iter()
+            .any(|object| object.kind == EnvironmentKind::Fence));
+    }
+}
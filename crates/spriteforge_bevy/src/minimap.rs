@@ -24,6 +24,7 @@ pub struct MiniMapSettings {
     pub grass_color: Color,
     pub dirt_color: Color,
     pub water_color: Color,
+    pub sand_color: Color,
     pub path_color: Color,
     pub area_color: Color,
     pub camera_color: Color,
@@ -43,6 +44,7 @@ impl Default for MiniMapSettings {
             grass_color: Color::srgba(0.15, 0.45, 0.22, 1.0),
             dirt_color: Color::srgba(0.45, 0.31, 0.2, 1.0),
             water_color: Color::srgba(0.2, 0.35, 0.7, 1.0),
+            sand_color: Color::srgba(0.78, 0.69, 0.48, 1.0),
             path_color: Color::srgba(0.95, 0.95, 0.95, 0.95),
             area_color: Color::srgba(0.95, 0.75, 0.2, 0.9),
             camera_color: Color::srgba(0.9, 0.2, 0.2, 0.9),
@@ -334,9 +336,10 @@ fn draw_base_tiles(
             let idx = (y * map_size.x + x) as usize;
             let color = match tiles.get(idx) {
                 Some(BaseTile::Grass) => settings.grass_color,
-                Some(BaseTile::Dirt) => settings.dirt_color,
-                Some(BaseTile::Path) => settings.path_color,
+                Some(BaseTile::Dirt | BaseTile::Farmland) => settings.dirt_color,
+                Some(BaseTile::Path | BaseTile::Bridge | BaseTile::Pier) => settings.path_color,
                 Some(BaseTile::Water) => settings.water_color,
+                Some(BaseTile::Sand) => settings.sand_color,
                 None => settings.grass_color,
             };
             let (rx, ry) = rotate_coord(x as i32, y as i32, map_size);
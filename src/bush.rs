@@ -0,0 +1,185 @@
+use std::f32::consts::{PI, TAU};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::tree::{TreeLeaf, TreeLeafStem, TreeModel, TreeSegment, Vec3};
+
+#[derive(Debug, Clone)]
+pub struct BushSettings {
+    /// Ground radius of the hemispherical foliage dome.
+    pub radius: f32,
+    pub height: f32,
+    pub stems: u32,
+    pub stem_radius: f32,
+    pub clusters: u32,
+    pub cluster_radius: f32,
+    pub cluster_leaves: u32,
+    pub leaf_size: f32,
+}
+
+impl Default for BushSettings {
+    fn default() -> Self {
+        Self {
+            radius: 2.2,
+            height: 1.6,
+            stems: 5,
+            stem_radius: 0.12,
+            clusters: 18,
+            cluster_radius: 0.7,
+            cluster_leaves: 16,
+            leaf_size: 0.28,
+        }
+    }
+}
+
+impl BushSettings {
+    /// The same bush at `fraction` of its mature size. Younger bushes keep the first clusters
+    /// of the mature one, scaled down, so every stage of a seed shares its layout.
+    pub fn at_growth(&self, fraction: f32) -> Self {
+        let scale = fraction.max(0.0).sqrt();
+        let clusters = (self.clusters as f32 * fraction.min(1.0)).ceil() as u32;
+        Self {
+            radius: self.radius * scale,
+            height: self.height * scale,
+            stem_radius: self.stem_radius * scale,
+            clusters: clusters.clamp(1, self.clusters.max(1)),
+            cluster_radius: self.cluster_radius * scale,
+            ..self.clone()
+        }
+    }
+}
+
+/// Builds a shrub as a `TreeModel`: several stems fan out from the ground, each cluster of
+/// leaves hangs off the nearest stem top, and the clusters overlap into a dense dome. Leaf
+/// normals point out of the dome, so the mask shades the bush as one rounded mass.
+pub fn generate_bush(seed: u64, settings: &BushSettings) -> TreeModel {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut segments = Vec::new();
+
+    let stem_count = settings.stems.max(1);
+    let mut stem_tips = Vec::with_capacity(stem_count as usize);
+    for stem in 0..stem_count {
+        let heading = (stem as f32 + rng.gen_range(0.0..0.8)) / stem_count as f32 * TAU;
+        let out = Vec3::new(heading.cos(), heading.sin(), 0.0);
+        let base = out * (settings.radius * rng.gen_range(0.05..0.2));
+        let top = out * (settings.radius * rng.gen_range(0.3..0.55))
+            + Vec3::new(0.0, 0.0, settings.height * rng.gen_range(0.45..0.65));
+        let bow = rng.gen_range(0.05..0.2) * settings.radius;
+        // Each stem bows outward on its way up, thinning toward the top.
+        let steps = 3;
+        let mut start = base;
+        let mut parent = None;
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            let end = base + (top - base) * t + out * (bow * (t * PI).sin());
+            segments.push(TreeSegment {
+                start,
+                end,
+                radius: settings.stem_radius * (1.0 - 0.4 * t),
+                normal: end.normalized(),
+                depth: step,
+                parent,
+            });
+            parent = Some(segments.len() - 1);
+            start = end;
+        }
+        stem_tips.push(segments.len() - 1);
+    }
+
+    let mut leaf_stems = Vec::new();
+    let mut leaves = Vec::new();
+    let shell = (settings.radius - settings.cluster_radius * 0.5).max(settings.radius * 0.5);
+    let dome = settings.height / settings.radius.max(f32::EPSILON);
+    for _ in 0..settings.clusters {
+        // Uniform heights spread the clusters evenly over the dome's surface area.
+        let heading = rng.gen_range(0.0..TAU);
+        let up = rng.gen_range(0.15..1.0f32);
+        let ring = (1.0 - up * up).sqrt();
+        let depth = rng.gen_range(0.6..1.0);
+        let center = Vec3::new(
+            heading.cos() * ring * shell * depth,
+            heading.sin() * ring * shell * depth,
+            (up * shell * dome * depth).max(settings.cluster_radius * 0.6),
+        );
+        let tip = *stem_tips
+            .iter()
+            .min_by(|a, b| {
+                let reach = |index: usize| (segments[index].end - center).length();
+                reach(**a).total_cmp(&reach(**b))
+            })
+            .unwrap_or(&0);
+        let twig_start = segments[tip].end;
+        segments.push(TreeSegment {
+            start: twig_start,
+            end: center,
+            radius: settings.stem_radius * 0.45,
+            normal: center.normalized(),
+            depth: segments[tip].depth + 1,
+            parent: Some(tip),
+        });
+        let twig = segments.len() - 1;
+
+        for _ in 0..settings.cluster_leaves {
+            let direction = Vec3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            )
+            .normalized();
+            let reach = settings.cluster_radius * rng.gen_range(0.3..1.0);
+            let mut position = center + direction * reach;
+            position.z = position.z.max(settings.leaf_size * 0.5);
+            let normal = (position.normalized() + direction * 0.5).normalized();
+            leaf_stems.push(TreeLeafStem {
+                start: center,
+                end: position,
+                radius: settings.leaf_size * 0.15,
+                normal,
+                segment: Some(twig),
+            });
+            leaves.push(TreeLeaf {
+                position,
+                size: settings.leaf_size,
+                normal,
+            });
+        }
+    }
+
+    TreeModel {
+        segments,
+        leaf_stems,
+        leaves,
+        iterations: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stems_rise_from_the_ground_under_a_leafy_dome() {
+        let settings = BushSettings::default();
+        let model = generate_bush(3, &settings);
+        let grounded = model
+            .segments
+            .iter()
+            .filter(|segment| segment.parent.is_none())
+            .collect::<Vec<_>>();
+        assert_eq!(grounded.len(), settings.stems as usize);
+        assert!(grounded.iter().all(|segment| segment.start.z == 0.0));
+        assert_eq!(
+            model.leaves.len(),
+            (settings.clusters * settings.cluster_leaves) as usize
+        );
+        let reach = settings.radius + settings.cluster_radius;
+        for leaf in &model.leaves {
+            assert!(leaf.position.z > 0.0);
+            assert!(leaf.position.x.hypot(leaf.position.y) <= reach);
+        }
+
+        let sapling = generate_bush(3, &settings.at_growth(0.3));
+        assert!(sapling.leaves.len() < model.leaves.len());
+    }
+}
@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 pub const DEFAULT_OUT_DIR: &str = "out/tilesheet";
 pub const TILESET_CONFIG_DIR: &str = "configs/tile";
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct TileConfig {
     pub name: String,
     pub sprite_width: Option<u32>,
@@ -21,9 +21,12 @@ pub struct TileConfig {
     pub grass_shades: Option<[String; 3]>,
     pub grass_edge_cutoff: Option<f32>,
     pub grass_edge_gradient: Option<f32>,
+    pub grass_density_noise: Option<NoiseConfig>,
+    pub grass_shade_noise: Option<NoiseConfig>,
     pub water_base: Option<String>,
     pub water_edge_cutoff: Option<f32>,
     pub water_edge_gradient: Option<f32>,
+    pub water_tone_noise: Option<NoiseConfig>,
     pub path_base: Option<String>,
     pub path_brick_count: Option<u32>,
     pub path_brick_crack: Option<f32>,
@@ -32,6 +35,91 @@ pub struct TileConfig {
     pub dirt_stones: Option<[String; 2]>,
     pub dirt_splotch_count: Option<u32>,
     pub dirt_stone_count: Option<u32>,
+    pub dirt_splotch_noise: Option<NoiseConfig>,
+    pub sand_base: Option<String>,
+    pub sand_grains: Option<[String; 2]>,
+    pub sand_wet: Option<String>,
+    pub sand_grain_density: Option<f32>,
+    pub sand_ripple_count: Option<u32>,
+    pub sand_wet_band: Option<f32>,
+    pub snow_base: Option<String>,
+    pub snow_shades: Option<[String; 2]>,
+    pub snow_sparkle: Option<String>,
+    pub snow_sparkle_density: Option<f32>,
+    pub snow_drift_count: Option<u32>,
+    pub snow_edge_cutoff: Option<f32>,
+    pub snow_edge_gradient: Option<f32>,
+    pub ice_base: Option<String>,
+    pub ice_crack: Option<String>,
+    pub ice_highlight: Option<String>,
+    pub ice_crack_count: Option<u32>,
+    pub ice_streak_count: Option<u32>,
+    pub ice_edge_cutoff: Option<f32>,
+    pub ice_edge_gradient: Option<f32>,
+    pub block_top: Option<String>,
+    pub block_height: Option<u32>,
+    pub block_face_style: Option<String>,
+    pub block_face_colors: Option<[String; 3]>,
+    pub block_strata_count: Option<u32>,
+    pub ramp_surface: Option<String>,
+    pub rock_sizes: Option<[f32; 3]>,
+    pub rock_variants: Option<u32>,
+    pub rock_squash: Option<f32>,
+    pub rock_roughness: Option<f32>,
+    pub rock_rings: Option<u32>,
+    pub rock_segments: Option<u32>,
+    pub rock_colors: Option<[String; 3]>,
+    pub decoration_variants: Option<u32>,
+    pub decoration_petal_count: Option<[u32; 2]>,
+    pub decoration_flower_colors: Option<Vec<String>>,
+    pub decoration_flower_center: Option<String>,
+    pub decoration_stem_color: Option<String>,
+    pub decoration_grass_colors: Option<[String; 2]>,
+    pub decoration_mushroom_colors: Option<[String; 3]>,
+    pub decoration_reed_colors: Option<[String; 2]>,
+    pub decoration_log_colors: Option<[String; 3]>,
+    pub fence_height: Option<u32>,
+    pub fence_rail_count: Option<u32>,
+    pub fence_colors: Option<[String; 3]>,
+    pub wall_height: Option<u32>,
+    pub wall_thickness: Option<f32>,
+    pub wall_course_height: Option<u32>,
+    pub wall_colors: Option<[String; 3]>,
+    pub bridge_materials: Option<Vec<String>>,
+    pub bridge_deck_height: Option<u32>,
+    pub bridge_width: Option<f32>,
+    pub bridge_rail_height: Option<u32>,
+    pub bridge_course_height: Option<u32>,
+    pub bridge_wood_colors: Option<[String; 3]>,
+    pub bridge_stone_colors: Option<[String; 3]>,
+    pub pier_deck_height: Option<u32>,
+    pub pier_width: Option<f32>,
+    pub pier_waterline: Option<u32>,
+    pub pier_colors: Option<[String; 3]>,
+    pub pier_water_color: Option<String>,
+    pub farmland_variants: Option<u32>,
+    pub farmland_furrow_count: Option<u32>,
+    pub farmland_dry_colors: Option<[String; 3]>,
+    pub farmland_wet_colors: Option<[String; 3]>,
+    pub farmland_puddle_color: Option<String>,
+    pub crop_stages: Option<u32>,
+    pub crop_variants: Option<u32>,
+    pub crop_rows: Option<u32>,
+    pub crop_wheat_colors: Option<[String; 3]>,
+    pub crop_corn_colors: Option<[String; 3]>,
+    pub crop_cabbage_colors: Option<[String; 3]>,
+    pub building_footprint: Option<[u32; 2]>,
+    pub building_tile_width: Option<u32>,
+    pub building_wall_height: Option<f32>,
+    pub building_roof_pitch: Option<f32>,
+    pub building_roof_styles: Option<Vec<String>>,
+    pub building_wall_materials: Option<Vec<String>>,
+    pub building_variants: Option<u32>,
+    pub building_plaster_colors: Option<[String; 3]>,
+    pub building_timber_colors: Option<[String; 3]>,
+    pub building_brick_colors: Option<[String; 3]>,
+    pub building_roof_colors: Option<Vec<[String; 3]>>,
+    pub building_window_colors: Option<[String; 3]>,
     pub transition_density: Option<f32>,
     pub transition_bias: Option<f32>,
     pub transition_falloff: Option<f32>,
@@ -47,6 +135,42 @@ pub struct TileConfig {
     pub tree_leaf_size: Option<f32>,
     pub tree_leaf_count: Option<u32>,
     pub tree_leaf_normal_bias: Option<f32>,
+    pub tree_crown_shape: Option<String>,
+    pub tree_tropism: Option<f32>,
+    pub tree_lean: Option<f32>,
+    pub tree_leaf_palette: Option<Vec<String>>,
+    pub tree_leaf_jitter: Option<f32>,
+    pub tree_seasons: Option<Vec<String>>,
+    pub tree_autumn_palette: Option<Vec<String>>,
+    pub tree_autumn_mix: Option<f32>,
+    pub tree_blossom_colors: Option<Vec<String>>,
+    pub tree_blossom_density: Option<f32>,
+    pub tree_sway_frames: Option<u32>,
+    pub tree_sway_amplitude: Option<f32>,
+    pub tree_growth_stages: Option<Vec<f32>>,
+    pub tree_view_rotations: Option<Vec<u32>>,
+    pub tree_supersample: Option<u32>,
+    pub tree_supersample_resolve: Option<String>,
+    pub tree_shadow_light: Option<[f32; 3]>,
+    pub tree_shadow_opacity: Option<f32>,
+    pub tree_shadow_blur: Option<f32>,
+    pub tree_bake_light: Option<bool>,
+    pub tree_light_direction: Option<[f32; 3]>,
+    pub tree_light_ambient: Option<f32>,
+    pub tree_light_diffuse: Option<f32>,
+    pub tree_light_bands: Option<u32>,
+    pub tree_attachments: Option<Vec<String>>,
+    pub tree_attachment_counts: Option<Vec<u32>>,
+    pub tree_attachment_sizes: Option<Vec<f32>>,
+    pub tree_attachment_colors: Option<Vec<String>>,
+    pub bush_radius: Option<f32>,
+    pub bush_height: Option<f32>,
+    pub bush_stems: Option<u32>,
+    pub bush_stem_radius: Option<f32>,
+    pub bush_clusters: Option<u32>,
+    pub bush_cluster_radius: Option<f32>,
+    pub bush_cluster_leaves: Option<u32>,
+    pub bush_leaf_size: Option<f32>,
     pub tree_trunk_color: Option<String>,
     pub tree_leaf_color: Option<String>,
 }
@@ -55,11 +179,40 @@ pub fn require_field<T>(value: Option<T>, field: &str) -> Result<T, String> {
     value.ok_or_else(|| format!("Missing required config field: {field}"))
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TilesheetEntry {
     pub seed: u64,
     pub overrides: TransitionOverrides,
     pub transition_mask: Option<u8>,
+    pub cliff_mask: Option<u8>,
+    pub rise_direction: Option<u8>,
+    pub size_class: Option<String>,
+    pub decoration: Option<String>,
+    pub connection_mask: Option<u8>,
+    pub landing_mask: Option<u8>,
+    pub material: Option<String>,
+    pub moisture: Option<String>,
+    pub crop: Option<String>,
+    pub stage: Option<u32>,
+    pub roof: Option<String>,
+    pub footprint_tiles: Option<[u32; 2]>,
+    pub season: Option<String>,
+    pub frame: Option<u32>,
+    pub rotation: Option<u32>,
+}
+
+/// Settings for a coherent noise field over a ground tile. `frequency` is the number of
+/// features across the tile and `amount` how strongly the field drives what it controls.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoiseConfig {
+    pub kind: String,
+    pub seed: u32,
+    pub frequency: f32,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub warp: f32,
+    pub amount: f32,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -71,6 +224,11 @@ pub struct TransitionOverrides {
     pub water_edge_gradient: Option<f32>,
     pub grass_edge_cutoff: Option<f32>,
     pub grass_edge_gradient: Option<f32>,
+    pub sand_wet_band: Option<f32>,
+    pub snow_edge_cutoff: Option<f32>,
+    pub snow_edge_gradient: Option<f32>,
+    pub ice_edge_cutoff: Option<f32>,
+    pub ice_edge_gradient: Option<f32>,
 }
 
 pub fn load_tile_config(path: &Path) -> Result<TileConfig, String> {
@@ -0,0 +1,310 @@
+use std::path::Path;
+
+use image::Rgba;
+use serde_json::json;
+
+use crate::tree::{TreeModel, Vec3, cross};
+
+const CYLINDER_SIDES: u32 = 8;
+const LEAF_ASPECT: f32 = 0.7;
+
+#[derive(Debug, Clone, Default)]
+pub struct TreeMesh {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+}
+
+pub fn build_tree_mesh(model: &TreeModel, trunk_color: Rgba<u8>, leaf_color: Rgba<u8>) -> TreeMesh {
+    let trunk = color_to_linear(trunk_color);
+    let leaf = color_to_linear(leaf_color);
+    let mut mesh = TreeMesh::default();
+
+    for segment in &model.segments {
+        push_cylinder(&mut mesh, segment.start, segment.end, segment.radius, trunk);
+    }
+    for stem in &model.leaf_stems {
+        push_cylinder(&mut mesh, stem.start, stem.end, stem.radius, trunk);
+    }
+    for (idx, leaf_item) in model.leaves.iter().enumerate() {
+        let along = model
+            .leaf_stems
+            .get(idx)
+            .map(|stem| stem.end - stem.start)
+            .unwrap_or(Vec3::new(0.0, 0.0, 1.0));
+        push_leaf_quad(
+            &mut mesh,
+            leaf_item.position,
+            leaf_item.size,
+            leaf_item.normal,
+            along,
+            leaf,
+        );
+    }
+
+    mesh
+}
+
+pub fn write_tree_mesh(path: &Path, mesh: &TreeMesh) -> Result<(), String> {
+    let extension = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase());
+    let bytes = match extension.as_deref() {
+        Some("obj") => mesh_to_obj(mesh).into_bytes(),
+        Some("glb") => mesh_to_glb(mesh)?,
+        _ => {
+            return Err(format!(
+                "Unsupported mesh format for {} (expected .obj or .glb)",
+                path.display()
+            ));
+        }
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, bytes).map_err(|e| e.to_string())
+}
+
+pub fn mesh_to_obj(mesh: &TreeMesh) -> String {
+    let mut out = String::from("# spriteforge tree mesh\n");
+    // Vertex colors use the common `v x y z r g b` extension.
+    for (position, color) in mesh.positions.iter().zip(&mesh.colors) {
+        out.push_str(&format!(
+            "v {:.5} {:.5} {:.5} {:.4} {:.4} {:.4}\n",
+            position[0], position[1], position[2], color[0], color[1], color[2]
+        ));
+    }
+    for normal in &mesh.normals {
+        out.push_str(&format!(
+            "vn {:.5} {:.5} {:.5}\n",
+            normal[0], normal[1], normal[2]
+        ));
+    }
+    for tri in mesh.indices.chunks_exact(3) {
+        let (a, b, c) = (tri[0] + 1, tri[1] + 1, tri[2] + 1);
+        out.push_str(&format!("f {a}//{a} {b}//{b} {c}//{c}\n"));
+    }
+    out
+}
+
+pub fn mesh_to_glb(mesh: &TreeMesh) -> Result<Vec<u8>, String> {
+    let vertex_count = mesh.positions.len();
+    if vertex_count == 0 {
+        return Err("Tree mesh has no vertices".to_string());
+    }
+
+    let mut bin = Vec::new();
+    let positions_offset = bin.len();
+    for position in &mesh.positions {
+        extend_f32(&mut bin, position);
+    }
+    let normals_offset = bin.len();
+    for normal in &mesh.normals {
+        extend_f32(&mut bin, normal);
+    }
+    let colors_offset = bin.len();
+    for color in &mesh.colors {
+        extend_f32(&mut bin, color);
+    }
+    let indices_offset = bin.len();
+    for index in &mesh.indices {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    let indices_len = bin.len() - indices_offset;
+    pad_to_four(&mut bin, 0);
+
+    let (min, max) = position_bounds(&mesh.positions);
+    let document = json!({
+        "asset": { "version": "2.0", "generator": "spriteforge" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0, "name": "tree" }],
+        "meshes": [{
+            "primitives": [{
+                "attributes": { "POSITION": 0, "NORMAL": 1, "COLOR_0": 2 },
+                "indices": 3,
+                "mode": 4
+            }]
+        }],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": vertex_count, "type": "VEC3", "min": min, "max": max },
+            { "bufferView": 1, "componentType": 5126, "count": vertex_count, "type": "VEC3" },
+            { "bufferView": 2, "componentType": 5126, "count": vertex_count, "type": "VEC4" },
+            { "bufferView": 3, "componentType": 5125, "count": mesh.indices.len(), "type": "SCALAR" }
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": positions_offset, "byteLength": normals_offset - positions_offset, "target": 34962 },
+            { "buffer": 0, "byteOffset": normals_offset, "byteLength": colors_offset - normals_offset, "target": 34962 },
+            { "buffer": 0, "byteOffset": colors_offset, "byteLength": indices_offset - colors_offset, "target": 34962 },
+            { "buffer": 0, "byteOffset": indices_offset, "byteLength": indices_len, "target": 34963 }
+        ],
+        "buffers": [{ "byteLength": bin.len() }]
+    });
+    let mut json_bytes = serde_json::to_vec(&document).map_err(|e| e.to_string())?;
+    pad_to_four(&mut json_bytes, b' ');
+
+    let total_len = 12 + 8 + json_bytes.len() + 8 + bin.len();
+    let mut out = Vec::with_capacity(total_len);
+    out.extend_from_slice(b"glTF");
+    out.extend_from_slice(&2u32.to_le_bytes());
+    out.extend_from_slice(&(total_len as u32).to_le_bytes());
+    out.extend_from_slice(&(json_bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"JSON");
+    out.extend_from_slice(&json_bytes);
+    out.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"BIN\0");
+    out.extend_from_slice(&bin);
+    Ok(out)
+}
+
+fn push_cylinder(mesh: &mut TreeMesh, start: Vec3, end: Vec3, radius: f32, color: [f32; 4]) {
+    let axis = end - start;
+    if axis.length() <= f32::EPSILON || radius <= 0.0 {
+        return;
+    }
+    let dir = axis.normalized();
+    let (axis_a, axis_b) = perpendicular_basis(dir);
+    let base = mesh.positions.len() as u32;
+    for side in 0..CYLINDER_SIDES {
+        let angle = side as f32 / CYLINDER_SIDES as f32 * std::f32::consts::TAU;
+        let radial = (axis_a * angle.cos() + axis_b * angle.sin()).normalized();
+        for point in [start, end] {
+            push_vertex(mesh, point + radial * radius, radial, color);
+        }
+    }
+    for side in 0..CYLINDER_SIDES {
+        let next = (side + 1) % CYLINDER_SIDES;
+        let a0 = base + side * 2;
+        let a1 = a0 + 1;
+        let b0 = base + next * 2;
+        let b1 = b0 + 1;
+        mesh.indices.extend_from_slice(&[a0, b0, a1, a1, b0, b1]);
+    }
+}
+
+fn push_leaf_quad(
+    mesh: &mut TreeMesh,
+    center: Vec3,
+    size: f32,
+    normal: Vec3,
+    along: Vec3,
+    color: [f32; 4],
+) {
+    let normal = if normal.length() <= f32::EPSILON {
+        Vec3::new(0.0, 0.0, 1.0)
+    } else {
+        normal.normalized()
+    };
+    let mut tangent = cross(cross(normal, along), normal);
+    if tangent.length() <= f32::EPSILON {
+        tangent = perpendicular_basis(normal).0;
+    }
+    let tangent = tangent.normalized() * size;
+    let bitangent = cross(normal, tangent).normalized() * (size * LEAF_ASPECT);
+    let corners = [
+        center - tangent - bitangent,
+        center + tangent - bitangent,
+        center + tangent + bitangent,
+        center - tangent + bitangent,
+    ];
+    // Leaves are single cards, so emit both faces to keep them visible from any side.
+    for (face_normal, order) in [
+        (normal, [0, 1, 2, 0, 2, 3]),
+        (normal * -1.0, [0, 2, 1, 0, 3, 2]),
+    ] {
+        let base = mesh.positions.len() as u32;
+        for corner in corners {
+            push_vertex(mesh, corner, face_normal, color);
+        }
+        mesh.indices
+            .extend(order.iter().map(|offset| base + offset));
+    }
+}
+
+fn push_vertex(mesh: &mut TreeMesh, position: Vec3, normal: Vec3, color: [f32; 4]) {
+    // The tree model is z-up; glTF and most OBJ consumers expect y-up.
+    mesh.positions.push([position.x, position.z, -position.y]);
+    mesh.normals.push([normal.x, normal.z, -normal.y]);
+    mesh.colors.push(color);
+}
+
+fn perpendicular_basis(dir: Vec3) -> (Vec3, Vec3) {
+    let mut axis_a = cross(dir, Vec3::new(0.0, 0.0, 1.0));
+    if axis_a.length() <= f32::EPSILON {
+        axis_a = cross(dir, Vec3::new(1.0, 0.0, 0.0));
+    }
+    let axis_a = axis_a.normalized();
+    let axis_b = cross(dir, axis_a).normalized();
+    (axis_a, axis_b)
+}
+
+fn color_to_linear(color: Rgba<u8>) -> [f32; 4] {
+    let [r, g, b, _] = color.0;
+    let to_linear = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    [to_linear(r), to_linear(g), to_linear(b), 1.0]
+}
+
+fn position_bounds(positions: &[[f32; 3]]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for position in positions {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(position[axis]);
+            max[axis] = max[axis].max(position[axis]);
+        }
+    }
+    (min, max)
+}
+
+fn extend_f32(bytes: &mut Vec<u8>, values: &[f32]) {
+    for value in values {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn pad_to_four(bytes: &mut Vec<u8>, fill: u8) {
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(fill);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{TreeSettings, generate_tree};
+
+    fn sample_mesh() -> TreeMesh {
+        let model = generate_tree(7, &TreeSettings::default());
+        build_tree_mesh(&model, Rgba([91, 58, 34, 255]), Rgba([59, 95, 37, 255]))
+    }
+
+    #[test]
+    fn mesh_indices_are_in_range() {
+        let mesh = sample_mesh();
+        assert!(!mesh.indices.is_empty());
+        assert_eq!(mesh.indices.len() % 3, 0);
+        assert_eq!(mesh.positions.len(), mesh.normals.len());
+        assert_eq!(mesh.positions.len(), mesh.colors.len());
+        let count = mesh.positions.len() as u32;
+        assert!(mesh.indices.iter().all(|index| *index < count));
+    }
+
+    #[test]
+    fn glb_has_valid_header() {
+        let bytes = mesh_to_glb(&sample_mesh()).expect("glb");
+        assert_eq!(&bytes[0..4], b"glTF");
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), 2);
+        let total = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        assert_eq!(total, bytes.len());
+        assert_eq!(total % 4, 0);
+    }
+}
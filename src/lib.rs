@@ -3,13 +3,21 @@ use std::path::{Path, PathBuf};
 
 use crate::config::{
     load_tile_config, output_path_for_config, require_field, TileConfig, TilesheetEntry,
-    TransitionOverrides, DEFAULT_OUT_DIR, TILESET_CONFIG_DIR,
+    DEFAULT_OUT_DIR, TILESET_CONFIG_DIR,
 };
-use crate::render::{parse_hex_color, render_tile, render_tilesheet, render_tilesheet_mask};
-use spriteforge_assets::{TileMetadata, TilesheetMetadata};
+use crate::render::{
+    building_layout, decoration_layout, parse_hex_color, render_tile, render_tilesheet,
+    plant_model, render_tilesheet_depth, render_tilesheet_mask, render_tilesheet_shadow,
+    tree_colors_from_config, TreeModelCache,
+    CROP_KINDS, DECORATION_KINDS, FARMLAND_MOISTURE, ROCK_SIZE_CLASSES,
+};
+use spriteforge_assets::{TileMetadata, TilesheetMetadata, EDGE_E, EDGE_N, EDGE_S, EDGE_W};
 
+mod bush;
 mod config;
+mod export;
 mod render;
+mod rock;
 mod tree;
 
 #[derive(Parser, Debug)]
@@ -34,6 +42,10 @@ pub struct Args {
     /// Path to JSON config file
     #[arg(long)]
     config: Option<PathBuf>,
+
+    /// Export the tree model for the seed as a mesh (.obj or .glb)
+    #[arg(long)]
+    mesh_out: Option<PathBuf>,
 }
 
 pub fn run() -> Result<(), String> {
@@ -43,6 +55,7 @@ pub fn run() -> Result<(), String> {
         && args.size.is_none()
         && args.bg.is_none()
         && args.seed.is_none()
+        && args.mesh_out.is_none()
     {
         build_all_tilesheets()?;
         return Ok(());
@@ -77,6 +90,7 @@ fn build_all_tilesheets() -> Result<(), String> {
             bg: None,
             seed: None,
             config: None,
+            mesh_out: None,
         };
         build_from_config_path(&path, &args)?;
     }
@@ -89,6 +103,9 @@ fn build_all_tilesheets() -> Result<(), String> {
 fn build_from_config_path(config_path: &Path, args: &Args) -> Result<(), String> {
     let out_path = output_path_for_config(config_path, args.out.as_ref(), DEFAULT_OUT_DIR);
     let tile_config = load_tile_config(config_path)?;
+    if let Some(mesh_path) = args.mesh_out.as_ref() {
+        export_tree_mesh(&tile_config, args, mesh_path)?;
+    }
     let image = build_from_tile_config(&tile_config, config_path, args, &out_path)?;
 
     if let Some(parent) = out_path.parent() {
@@ -99,6 +116,25 @@ fn build_from_config_path(config_path: &Path, args: &Args) -> Result<(), String>
     Ok(())
 }
 
+fn export_tree_mesh(tile_config: &TileConfig, args: &Args, mesh_path: &Path) -> Result<(), String> {
+    if tile_config.name != "tree" && tile_config.name != "bush" {
+        return Err(format!(
+            "Mesh export is only supported for tree configs, got: {}",
+            tile_config.name
+        ));
+    }
+    let seed = match args.seed {
+        Some(seed) => seed,
+        None => require_field(tile_config.seed, "seed")?,
+    };
+    let (trunk_color, leaf_color) = tree_colors_from_config(tile_config)?;
+    let model = plant_model(tile_config, seed)?;
+    let mesh = export::build_tree_mesh(&model, trunk_color, leaf_color);
+    export::write_tree_mesh(mesh_path, &mesh)?;
+    println!("Saved tree mesh to {}", mesh_path.display());
+    Ok(())
+}
+
 fn build_from_tile_config(
     tile_config: &TileConfig,
     config_path: &Path,
@@ -117,10 +153,7 @@ fn build_from_tile_config(
         bg_hex = override_bg;
     }
     let bg = parse_hex_color(&bg_hex)?;
-    let is_transition = matches!(
-        tile_config.name.as_str(),
-        "grass_transition" | "water_transition" | "path_transition" | "debug_weight"
-    );
+    let is_transition = is_transition_tile(&tile_config.name);
     let has_tilesheet = tile_config.tilesheet_count.is_some()
         || tile_config.tilesheet_seed_start.is_some()
         || is_transition;
@@ -140,42 +173,77 @@ fn build_from_tile_config(
     let columns = require_field(tile_config.tilesheet_columns, "tilesheet_columns")?.max(1);
     let padding = require_field(tile_config.tilesheet_padding, "tilesheet_padding")?;
     let entries = build_tilesheet_entries(tile_config)?;
+    let trees = TreeModelCache::default();
     let image = render_tilesheet(
         sprite_width,
         sprite_height,
         bg,
         tile_config,
+        &trees,
         &entries,
         columns,
         padding,
     )?;
     if tile_config.name == "water"
         || tile_config.name == "water_transition"
+        || tile_config.name == "ice"
+        || tile_config.name == "ice_transition"
+        || tile_config.name == "rock"
         || tile_config.name == "tree"
         || tile_config.name == "bush"
+        || tile_config.name == "building"
     {
         let mask = render_tilesheet_mask(
             sprite_width,
             sprite_height,
             tile_config,
+            &trees,
             &entries,
             columns,
             padding,
         )?;
-        let mask_path = mask_output_path(out_path);
+        let mask_path = suffixed_output_path(out_path, "mask");
         if let Some(parent) = mask_path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
         }
         mask.save(&mask_path).map_err(|e| e.to_string())?;
         println!("Saved tilesheet mask to {}", mask_path.display());
     }
+    let is_plant = tile_config.name == "tree" || tile_config.name == "bush";
+    if is_plant && tile_config.tree_shadow_light.is_some() {
+        let shadow = render_tilesheet_shadow(
+            sprite_width,
+            sprite_height,
+            tile_config,
+            &trees,
+            &entries,
+            columns,
+            padding,
+        )?;
+        let shadow_path = suffixed_output_path(out_path, "shadow");
+        shadow.save(&shadow_path).map_err(|e| e.to_string())?;
+        println!("Saved tilesheet shadow to {}", shadow_path.display());
+    }
+    if is_plant {
+        let depth = render_tilesheet_depth(
+            sprite_width,
+            sprite_height,
+            tile_config,
+            &trees,
+            &entries,
+            columns,
+            padding,
+        )?;
+        let depth_path = suffixed_output_path(out_path, "depth");
+        depth.save(&depth_path).map_err(|e| e.to_string())?;
+        println!("Saved tilesheet depth to {}", depth_path.display());
+    }
     write_tilesheet_metadata(
         out_path,
         &entries,
         sprite_width,
         sprite_height,
-        columns,
-        padding,
+        tile_config,
         config_path,
     )?;
     Ok(image)
@@ -183,18 +251,208 @@ fn build_from_tile_config(
 
 fn build_tilesheet_entries(tile_config: &TileConfig) -> Result<Vec<TilesheetEntry>, String> {
     let seed_start = require_field(tile_config.tilesheet_seed_start, "tilesheet_seed_start")?;
-    if matches!(
-        tile_config.name.as_str(),
-        "grass_transition" | "water_transition" | "path_transition" | "debug_weight"
-    ) {
+    if is_transition_tile(&tile_config.name) {
         let masks = spriteforge_assets::all_transition_masks();
         return Ok(masks
             .iter()
             .enumerate()
             .map(|(index, mask)| TilesheetEntry {
                 seed: seed_start + index as u64,
-                overrides: TransitionOverrides::default(),
                 transition_mask: Some(*mask),
+                ..Default::default()
+            })
+            .collect());
+    }
+    if tile_config.name == "block" {
+        let masks = spriteforge_assets::all_cliff_masks();
+        return Ok(masks
+            .iter()
+            .enumerate()
+            .map(|(index, mask)| TilesheetEntry {
+                seed: seed_start + index as u64,
+                cliff_mask: Some(*mask),
+                ..Default::default()
+            })
+            .collect());
+    }
+    if matches!(tile_config.name.as_str(), "fence" | "wall" | "pier") {
+        let masks = if tile_config.name == "pier" {
+            spriteforge_assets::all_pier_masks()
+        } else {
+            spriteforge_assets::all_connection_masks()
+        };
+        return Ok(masks
+            .iter()
+            .enumerate()
+            .map(|(index, mask)| TilesheetEntry {
+                seed: seed_start + index as u64,
+                connection_mask: Some(*mask),
+                ..Default::default()
+            })
+            .collect());
+    }
+    if tile_config.name == "bridge" {
+        let materials = require_field(tile_config.bridge_materials.clone(), "bridge_materials")?;
+        let mut entries = Vec::new();
+        for material in materials {
+            for (axis, landing) in spriteforge_assets::all_bridge_pieces() {
+                entries.push(TilesheetEntry {
+                    seed: seed_start + entries.len() as u64,
+                    connection_mask: Some(axis),
+                    landing_mask: Some(landing),
+                    material: Some(material.clone()),
+                    ..Default::default()
+                });
+            }
+        }
+        return Ok(entries);
+    }
+    if tile_config.name == "rock" {
+        let variants = require_field(tile_config.rock_variants, "rock_variants")?.max(1);
+        let mut entries = Vec::new();
+        for size_class in ROCK_SIZE_CLASSES {
+            for _ in 0..variants {
+                entries.push(TilesheetEntry {
+                    seed: seed_start + entries.len() as u64,
+                    size_class: Some(size_class.to_string()),
+                    ..Default::default()
+                });
+            }
+        }
+        return Ok(entries);
+    }
+    if tile_config.name == "decoration" {
+        let variants =
+            require_field(tile_config.decoration_variants, "decoration_variants")?.max(1);
+        let mut entries = Vec::new();
+        for kind in DECORATION_KINDS {
+            for _ in 0..variants {
+                entries.push(TilesheetEntry {
+                    seed: seed_start + entries.len() as u64,
+                    decoration: Some(kind.to_string()),
+                    ..Default::default()
+                });
+            }
+        }
+        return Ok(entries);
+    }
+    if tile_config.name == "farmland" {
+        let variants = require_field(tile_config.farmland_variants, "farmland_variants")?.max(1);
+        let mut entries = Vec::new();
+        for moisture in FARMLAND_MOISTURE {
+            for axis in [EDGE_N | EDGE_S, EDGE_E | EDGE_W] {
+                for _ in 0..variants {
+                    entries.push(TilesheetEntry {
+                        seed: seed_start + entries.len() as u64,
+                        connection_mask: Some(axis),
+                        moisture: Some(moisture.to_string()),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        return Ok(entries);
+    }
+    if tile_config.name == "crop" {
+        let stages = require_field(tile_config.crop_stages, "crop_stages")?;
+        let variants = require_field(tile_config.crop_variants, "crop_variants")?.max(1);
+        let mut entries = Vec::new();
+        for kind in CROP_KINDS {
+            for stage in 0..stages {
+                for _ in 0..variants {
+                    entries.push(TilesheetEntry {
+                        seed: seed_start + entries.len() as u64,
+                        crop: Some(kind.to_string()),
+                        stage: Some(stage),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        return Ok(entries);
+    }
+    if tile_config.name == "building" {
+        let footprint = require_field(tile_config.building_footprint, "building_footprint")?;
+        let roofs = require_field(
+            tile_config.building_roof_styles.clone(),
+            "building_roof_styles",
+        )?;
+        let materials = require_field(
+            tile_config.building_wall_materials.clone(),
+            "building_wall_materials",
+        )?;
+        let variants = require_field(tile_config.building_variants, "building_variants")?.max(1);
+        let mut entries = Vec::new();
+        for roof in &roofs {
+            for material in &materials {
+                for _ in 0..variants {
+                    entries.push(TilesheetEntry {
+                        seed: seed_start + entries.len() as u64,
+                        material: Some(material.clone()),
+                        roof: Some(roof.clone()),
+                        footprint_tiles: Some(footprint),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        return Ok(entries);
+    }
+    if tile_config.name == "tree" || tile_config.name == "bush" {
+        // Every growth stage, season, view rotation and sway frame of one tree shares its seed,
+        // so the shape carries across the group.
+        let count = require_field(tile_config.tilesheet_count, "tilesheet_count")?;
+        let seasons = tile_config
+            .tree_seasons
+            .clone()
+            .unwrap_or_else(|| vec!["summer".to_string()]);
+        let frames = tile_config.tree_sway_frames.unwrap_or(1);
+        let frames: Vec<Option<u32>> = if frames > 1 {
+            (0..frames).map(Some).collect()
+        } else {
+            vec![None]
+        };
+        // A lone 1.0 stage, or none, is the mature tree alone; anything else is regrown.
+        let stages = tile_config.tree_growth_stages.clone().unwrap_or_default();
+        let stages: Vec<Option<u32>> = if stages.len() > 1 || stages.iter().any(|f| *f != 1.0) {
+            (0..stages.len() as u32).map(Some).collect()
+        } else {
+            vec![None]
+        };
+        let rotations = tile_config.tree_view_rotations.clone().unwrap_or_else(|| vec![0]);
+        if let Some(rotation) = rotations.iter().find(|rotation| **rotation > 3) {
+            return Err(format!("Tree view rotation {rotation} is not a quarter turn of 0-3"));
+        }
+        let mut entries = Vec::new();
+        for index in 0..count as u64 {
+            for stage in &stages {
+                for season in &seasons {
+                    for rotation in &rotations {
+                        for frame in &frames {
+                            entries.push(TilesheetEntry {
+                                seed: seed_start + index,
+                                stage: *stage,
+                                season: Some(season.clone()),
+                                frame: *frame,
+                                rotation: Some(*rotation),
+                                ..Default::default()
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        return Ok(entries);
+    }
+    if tile_config.name == "ramp" {
+        let directions = spriteforge_assets::all_ramp_directions();
+        return Ok(directions
+            .iter()
+            .enumerate()
+            .map(|(index, direction)| TilesheetEntry {
+                seed: seed_start + index as u64,
+                rise_direction: Some(*direction),
+                ..Default::default()
             })
             .collect());
     }
@@ -202,18 +460,30 @@ fn build_tilesheet_entries(tile_config: &TileConfig) -> Result<Vec<TilesheetEntr
     Ok((0..count)
         .map(|index| TilesheetEntry {
             seed: seed_start + index as u64,
-            overrides: TransitionOverrides::default(),
-            transition_mask: None,
+            ..Default::default()
         })
         .collect())
 }
 
-fn mask_output_path(out_path: &Path) -> std::path::PathBuf {
+fn is_transition_tile(name: &str) -> bool {
+    matches!(
+        name,
+        "grass_transition"
+            | "water_transition"
+            | "path_transition"
+            | "sand_transition"
+            | "snow_transition"
+            | "ice_transition"
+            | "debug_weight"
+    )
+}
+
+fn suffixed_output_path(out_path: &Path, suffix: &str) -> std::path::PathBuf {
     let stem = out_path
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("tilesheet");
-    let file_name = format!("{stem}_mask.png");
+    let file_name = format!("{stem}_{suffix}.png");
     out_path.with_file_name(file_name)
 }
 
@@ -222,11 +492,11 @@ fn write_tilesheet_metadata(
     entries: &[TilesheetEntry],
     sprite_width: u32,
     sprite_height: u32,
-    columns: u32,
-    padding: u32,
+    tile_config: &TileConfig,
     config_path: &Path,
 ) -> Result<(), String> {
-    let cols = columns.max(1);
+    let cols = require_field(tile_config.tilesheet_columns, "tilesheet_columns")?.max(1);
+    let padding = require_field(tile_config.tilesheet_padding, "tilesheet_padding")?;
     let rows = ((entries.len() as u32) + cols - 1) / cols;
     let mut tiles = Vec::with_capacity(entries.len());
     for (i, entry) in entries.iter().enumerate() {
@@ -234,6 +504,11 @@ fn write_tilesheet_metadata(
         let row = (i as u32) / cols;
         let x = col * sprite_width + padding * col;
         let y = row * sprite_height + padding * row;
+        let layout = match (entry.decoration.as_deref(), entry.footprint_tiles) {
+            (Some(kind), _) => Some(decoration_layout(kind, sprite_width, sprite_height)?),
+            (None, Some(_)) => Some(building_layout(tile_config, sprite_width, sprite_height)?),
+            (None, None) => None,
+        };
         tiles.push(TileMetadata {
             index: i,
             row,
@@ -244,9 +519,65 @@ fn write_tilesheet_metadata(
             height: sprite_height,
             seed: entry.seed,
             transition_mask: entry.transition_mask,
+            cliff_mask: entry.cliff_mask,
+            rise_direction: entry.rise_direction,
+            size_class: entry.size_class.clone(),
+            decoration: entry.decoration.clone(),
+            footprint: layout.map(|(footprint, _)| footprint),
+            anchor: layout.map(|(_, anchor)| anchor),
+            connection_mask: entry.connection_mask,
+            landing_mask: entry.landing_mask,
+            material: entry.material.clone(),
+            moisture: entry.moisture.clone(),
+            crop: entry.crop.clone(),
+            stage: entry.stage,
+            roof: entry.roof.clone(),
+            footprint_tiles: entry.footprint_tiles,
+            season: entry.season.clone(),
+            frame: entry.frame,
+            rotation: entry.rotation,
         });
     }
 
+    // Consecutive frames of one animation form a group, starting at frame 0.
+    let mut frame_groups: Vec<Vec<usize>> = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        match (entry.frame, frame_groups.last_mut()) {
+            (Some(0), _) | (Some(_), None) => frame_groups.push(vec![i]),
+            (Some(_), Some(group)) => group.push(i),
+            (None, _) => {}
+        }
+    }
+
+    // Growth stages of one tree share its seed; each season, rotation and frame gets its own
+    // group.
+    let mut growth_keys = Vec::new();
+    let mut growth_groups: Vec<Vec<usize>> = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.season.is_none() || entry.stage.is_none() {
+            continue;
+        }
+        let key = (entry.seed, entry.season.as_deref(), entry.rotation, entry.frame);
+        match growth_keys.iter().position(|group| *group == key) {
+            Some(group) => growth_groups[group].push(i),
+            None => {
+                growth_keys.push(key);
+                growth_groups.push(vec![i]);
+            }
+        }
+    }
+
+    // The stage grown closest to full size stands in for the tree in painted maps.
+    let mature_stage = tile_config
+        .tree_growth_stages
+        .as_ref()
+        .filter(|_| entries.iter().any(|entry| entry.season.is_some() && entry.stage.is_some()))
+        .and_then(|stages| {
+            (0..stages.len())
+                .min_by(|a, b| (stages[*a] - 1.0).abs().total_cmp(&(stages[*b] - 1.0).abs()))
+        })
+        .map(|stage| stage as u32);
+
     let meta = TilesheetMetadata {
         image: out_path.to_string_lossy().to_string(),
         config: config_path.to_string_lossy().to_string(),
@@ -257,6 +588,9 @@ fn write_tilesheet_metadata(
         padding,
         tile_count: entries.len(),
         tiles,
+        frame_groups,
+        growth_groups,
+        mature_stage,
     };
 
     let json = serde_json::to_string_pretty(&meta).map_err(|e| e.to_string())?;
@@ -268,3 +602,64 @@ fn write_tilesheet_metadata(
     println!("Saved tilesheet metadata to {}", meta_path.display());
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_shipped_config_builds_and_renders_its_first_tile() {
+        let mut paths: Vec<_> = std::fs::read_dir(TILESET_CONFIG_DIR)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "config"))
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty());
+        for path in paths {
+            let config = load_tile_config(&path).unwrap();
+            let entries = build_tilesheet_entries(&config)
+                .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+            assert!(!entries.is_empty(), "{} has no tiles", path.display());
+            let width = config.sprite_width.unwrap();
+            let height = config.sprite_height.unwrap();
+            let bg = parse_hex_color(config.bg.as_deref().unwrap()).unwrap();
+            // Transition sheets draw every mask regardless of the entries passed in.
+            let entry = &entries[0];
+            let rendered = if config.name.ends_with("_transition") {
+                render_tile(
+                    width,
+                    height,
+                    bg,
+                    entry.seed,
+                    &config,
+                    entry.transition_mask,
+                    Some(&entry.overrides),
+                )
+            } else {
+                let trees = TreeModelCache::default();
+                render_tilesheet(width, height, bg, &config, &trees, &entries[..1], 1, 0)
+            };
+            rendered.unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        }
+    }
+
+    #[test]
+    fn a_single_partial_growth_stage_is_still_regrown() {
+        let mut config = TileConfig {
+            name: "tree".to_string(),
+            tilesheet_seed_start: Some(1),
+            tilesheet_count: Some(2),
+            tree_growth_stages: Some(vec![0.5]),
+            ..Default::default()
+        };
+        let entries = build_tilesheet_entries(&config).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.stage == Some(0)));
+        assert!(entries.iter().all(|entry| entry.season.as_deref() == Some("summer")));
+
+        config.tree_growth_stages = Some(vec![1.0]);
+        let entries = build_tilesheet_entries(&config).unwrap();
+        assert!(entries.iter().all(|entry| entry.stage.is_none()));
+    }
+}
@@ -0,0 +1,311 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::util::{blit_offset, mix_color, parse_hex_color};
+use spriteforge_assets::{uv_from_xy, EDGE_E, EDGE_N, EDGE_S, EDGE_W};
+
+const RIGHT_FACE_SHADE: f32 = 0.75;
+const BOTTOM_FACE_SHADE: f32 = 0.8;
+const LIP_WIDTH: f32 = 3.0;
+
+#[derive(Clone, Copy)]
+enum FaceStyle {
+    Strata,
+    Rock,
+    Dirt,
+}
+
+/// Renders a terrain block raised by `block_height` pixels. The left and right side faces
+/// are only drawn when the south and east neighbors in `cliff_mask` are lower; lower north
+/// and west neighbors get a darkened lip along the back edges of the top.
+pub fn render_block_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    cliff_mask: u8,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if config.name != "block" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let top_name = require_field(config.block_top.clone(), "block_top")?;
+    if top_name == "block" {
+        return Err("block_top cannot be another block".to_string());
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let faces = BlockFaces::from_config(&mut rng, config, sprite_width, sprite_height)?;
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    faces.draw_side_faces(&mut img, &mut rng, |edge, _| {
+        if cliff_mask & edge != 0 { 1.0 } else { 0.0 }
+    });
+
+    // The top reuses the regular terrain renderer and is lifted by the block height.
+    let mut top_config = config.clone();
+    top_config.name = top_name;
+    let mut top = super::render_tile(
+        sprite_width,
+        sprite_height,
+        Rgba([0, 0, 0, 0]),
+        seed,
+        &top_config,
+        None,
+        None,
+    )?;
+    darken_back_lips(&mut top, cliff_mask, faces.palette[1]);
+    blit_offset(&mut img, &top, 0, -(faces.height as i32));
+    Ok(img)
+}
+
+/// Side face settings shared by blocks and ramps so cliffs and ramp walls match.
+pub(super) struct BlockFaces {
+    pub(super) height: u32,
+    pub(super) palette: [Rgba<u8>; 3],
+    features: FaceFeatures,
+}
+
+impl BlockFaces {
+    pub(super) fn from_config(
+        rng: &mut StdRng,
+        config: &TileConfig,
+        sprite_width: u32,
+        sprite_height: u32,
+    ) -> Result<Self, String> {
+        let height = require_field(config.block_height, "block_height")?;
+        if height + sprite_width / 2 > sprite_height {
+            return Err(format!(
+                "block_height {height} does not fit in a {sprite_width}x{sprite_height} sprite"
+            ));
+        }
+        let style = match require_field(config.block_face_style.clone(), "block_face_style")?
+            .as_str()
+        {
+            "strata" => FaceStyle::Strata,
+            "rock" => FaceStyle::Rock,
+            "dirt" => FaceStyle::Dirt,
+            other => return Err(format!("Unknown block_face_style: {other}")),
+        };
+        let face_hexes = require_field(config.block_face_colors.clone(), "block_face_colors")?;
+        let palette = [
+            parse_hex_color(&face_hexes[0])?,
+            parse_hex_color(&face_hexes[1])?,
+            parse_hex_color(&face_hexes[2])?,
+        ];
+        let features = FaceFeatures::generate(rng, style, sprite_width, height, config)?;
+        Ok(Self {
+            height,
+            palette,
+            features,
+        })
+    }
+
+    /// Draws the left (south) and right (east) side faces. `rise` returns the face height as
+    /// a fraction of the block height for an edge bit and a position along that edge, where
+    /// 0 is the left end of the edge on screen.
+    pub(super) fn draw_side_faces<F>(
+        &self,
+        img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
+        rng: &mut StdRng,
+        rise: F,
+    ) where
+        F: Fn(u8, f32) -> f32,
+    {
+        let width_f = img.width().saturating_sub(1) as f32;
+        let bottom_y = img.height().saturating_sub(1) as f32;
+        let cx = width_f / 2.0;
+        let mid_y = bottom_y - width_f / 4.0;
+        let height_f = self.height as f32;
+        if self.height == 0 || width_f <= 0.0 {
+            return;
+        }
+        for x in 0..img.width() {
+            let xf = x as f32;
+            let (edge_y, face_height, shade) = if xf <= cx {
+                (
+                    mid_y + (xf / cx) * (bottom_y - mid_y),
+                    rise(EDGE_S, xf / cx) * height_f,
+                    1.0,
+                )
+            } else {
+                (
+                    bottom_y - ((xf - cx) / cx) * (bottom_y - mid_y),
+                    rise(EDGE_E, (xf - cx) / cx) * height_f,
+                    RIGHT_FACE_SHADE,
+                )
+            };
+            if face_height <= 0.0 {
+                continue;
+            }
+            let face_top = edge_y - face_height;
+            let top = face_top.ceil().max(0.0) as u32;
+            let bottom = edge_y.floor().min(bottom_y) as u32;
+            for y in top..=bottom {
+                // Depth is measured from the full block top so strata line up across tiles.
+                let depth = y as f32 - (edge_y - height_f);
+                let mut color = self.features.color_at(rng, &self.palette, xf, depth);
+                let occlusion = 1.0 - (1.0 - BOTTOM_FACE_SHADE) * (depth / height_f);
+                color = shade_color(color, shade * occlusion);
+                img.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+fn darken_back_lips(top: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, cliff_mask: u8, rim: Rgba<u8>) {
+    if cliff_mask & (EDGE_N | EDGE_W) == 0 {
+        return;
+    }
+    let width = top.width().max(1) as f32;
+    let lip = LIP_WIDTH * 2.0 / width;
+    for (x, y, pixel) in top.enumerate_pixels_mut() {
+        if pixel.0[3] == 0 {
+            continue;
+        }
+        let (u, v) = uv_from_xy(x as f32 / width, y as f32 / width);
+        let mut edge_distance = f32::MAX;
+        if cliff_mask & EDGE_W != 0 && (0.0..=1.0).contains(&v) {
+            edge_distance = edge_distance.min(u);
+        }
+        if cliff_mask & EDGE_N != 0 && (0.0..=1.0).contains(&u) {
+            edge_distance = edge_distance.min(1.0 - v);
+        }
+        if edge_distance < lip {
+            let alpha = pixel.0[3];
+            *pixel = mix_color(*pixel, rim, 0.6 * (1.0 - edge_distance / lip));
+            pixel.0[3] = alpha;
+        }
+    }
+}
+
+enum FaceFeatures {
+    Strata {
+        bands: Vec<(f32, usize)>,
+        wobble_phase: f32,
+        wobble_amplitude: f32,
+    },
+    Rock {
+        cells: Vec<(f32, f32, usize)>,
+    },
+    Dirt {
+        pebbles: Vec<(f32, f32, f32)>,
+    },
+}
+
+impl FaceFeatures {
+    fn generate(
+        rng: &mut StdRng,
+        style: FaceStyle,
+        sprite_width: u32,
+        block_height: u32,
+        config: &TileConfig,
+    ) -> Result<Self, String> {
+        let width = sprite_width as f32;
+        let height = block_height.max(1) as f32;
+        Ok(match style {
+            FaceStyle::Strata => {
+                let count = require_field(config.block_strata_count, "block_strata_count")?.max(1);
+                let weights: Vec<f32> = (0..count).map(|_| rng.gen_range(0.5..1.5)).collect();
+                let total: f32 = weights.iter().sum();
+                let mut bottom = 0.0;
+                let mut bands = Vec::with_capacity(weights.len());
+                for (index, weight) in weights.iter().enumerate() {
+                    bottom += weight / total * height;
+                    let shade = if index % 2 == 0 { 0 } else { rng.gen_range(1..3) };
+                    bands.push((bottom, shade));
+                }
+                FaceFeatures::Strata {
+                    bands,
+                    wobble_phase: rng.gen_range(0.0..std::f32::consts::TAU),
+                    wobble_amplitude: rng.gen_range(1.0..3.0),
+                }
+            }
+            FaceStyle::Rock => {
+                let count = ((width * height) / 600.0).ceil().max(3.0) as usize;
+                let cells = (0..count)
+                    .map(|_| {
+                        (
+                            rng.gen_range(0.0..width),
+                            rng.gen_range(0.0..height),
+                            rng.gen_range(0..3),
+                        )
+                    })
+                    .collect();
+                FaceFeatures::Rock { cells }
+            }
+            FaceStyle::Dirt => {
+                let count = ((width * height) / 900.0).ceil() as usize;
+                let pebbles = (0..count)
+                    .map(|_| {
+                        (
+                            rng.gen_range(0.0..width),
+                            rng.gen_range(0.0..height),
+                            rng.gen_range(1.0..2.5),
+                        )
+                    })
+                    .collect();
+                FaceFeatures::Dirt { pebbles }
+            }
+        })
+    }
+
+    fn color_at(&self, rng: &mut StdRng, palette: &[Rgba<u8>; 3], x: f32, depth: f32) -> Rgba<u8> {
+        match self {
+            FaceFeatures::Strata {
+                bands,
+                wobble_phase,
+                wobble_amplitude,
+            } => {
+                let wobbled = depth + (x * 0.08 + wobble_phase).sin() * wobble_amplitude;
+                let band = bands
+                    .iter()
+                    .find(|(bottom, _)| wobbled < *bottom)
+                    .or(bands.last())
+                    .map(|(_, shade)| *shade)
+                    .unwrap_or(0);
+                let mut color = palette[band];
+                if rng.gen_bool(0.08) {
+                    color = mix_color(color, palette[1], 0.4);
+                }
+                color
+            }
+            FaceFeatures::Rock { cells } => {
+                let mut nearest = (f32::MAX, 0);
+                let mut second = f32::MAX;
+                for (cx, cy, shade) in cells {
+                    let distance = ((x - cx).powi(2) + (depth - cy).powi(2)).sqrt();
+                    if distance < nearest.0 {
+                        second = nearest.0;
+                        nearest = (distance, *shade);
+                    } else if distance < second {
+                        second = distance;
+                    }
+                }
+                if second - nearest.0 < 1.5 {
+                    palette[1]
+                } else {
+                    mix_color(palette[0], palette[nearest.1], 0.5)
+                }
+            }
+            FaceFeatures::Dirt { pebbles } => {
+                for (px, py, radius) in pebbles {
+                    if (x - px).powi(2) + (depth - py).powi(2) <= radius * radius {
+                        return palette[2];
+                    }
+                }
+                match rng.gen_range(0..10) {
+                    0 => palette[1],
+                    1 => palette[2],
+                    _ => palette[0],
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn shade_color(color: Rgba<u8>, factor: f32) -> Rgba<u8> {
+    let [r, g, b, a] = color.0;
+    let scale = |c: u8| (c as f32 * factor).round().clamp(0.0, 255.0) as u8;
+    Rgba([scale(r), scale(g), scale(b), a])
+}
@@ -0,0 +1,258 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::fence::{draw_boxes, stone_variation, Face, IsoBox, Projector, StoneCoursing};
+use crate::render::util::{mix_color, parse_hex_color};
+use spriteforge_assets::{EDGE_E, EDGE_N, EDGE_S, EDGE_W};
+
+pub const BRIDGE_MATERIALS: [&str; 2] = ["wood", "stone"];
+
+const SUPPORT: u8 = 0;
+const DECK: u8 = 1;
+const RAILING: u8 = 2;
+const RAIL_SEGMENTS: u32 = 4;
+
+type Sprite = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+/// One bridge tile variant. The deck runs between the two edges in `connection_mask`
+/// (`EDGE_N | EDGE_S` or `EDGE_E | EDGE_W`); edges in `landing_mask` meet the shore and get
+/// an abutment instead of continuing over water.
+#[derive(Clone, Copy)]
+pub struct BridgePiece<'a> {
+    pub connection_mask: u8,
+    pub landing_mask: u8,
+    pub material: &'a str,
+}
+
+pub fn render_bridge_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    piece: BridgePiece,
+) -> Result<Sprite, String> {
+    let BridgePiece {
+        connection_mask,
+        landing_mask,
+        material,
+    } = piece;
+    if config.name != "bridge" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let along_v = match connection_mask {
+        mask if mask == EDGE_N | EDGE_S => true,
+        mask if mask == EDGE_E | EDGE_W => false,
+        other => {
+            return Err(format!(
+                "Bridge connection mask must be one axis, got {other}"
+            ))
+        }
+    };
+    if landing_mask & !connection_mask != 0 {
+        return Err(format!(
+            "Bridge landing mask {landing_mask} is not on the deck axis {connection_mask}"
+        ));
+    }
+    let deck_height = require_field(config.bridge_deck_height, "bridge_deck_height")? as f32;
+    let half_width = require_field(config.bridge_width, "bridge_width")?.clamp(0.1, 1.0) * 0.5;
+    let rail_height = require_field(config.bridge_rail_height, "bridge_rail_height")? as f32;
+    // Landing at the low end of the axis (S or W) or at the high end (N or E).
+    let lands_low = landing_mask & (EDGE_S | EDGE_W) != 0;
+    let lands_high = landing_mask & (EDGE_N | EDGE_E) != 0;
+    let deck = Deck {
+        along_v,
+        half_width,
+        height: deck_height,
+        rail_height,
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    let projector = Projector::new(sprite_width, sprite_height);
+    let unit = projector.width * 0.5;
+    match material {
+        "wood" => {
+            let hexes = require_field(config.bridge_wood_colors.clone(), "bridge_wood_colors")?;
+            let [light, base, dark] = [
+                parse_hex_color(&hexes[0])?,
+                parse_hex_color(&hexes[1])?,
+                parse_hex_color(&hexes[2])?,
+            ];
+            let mut boxes = wood_boxes(&deck, lands_low, lands_high);
+            let plank = (unit / 14.0).max(3.0);
+            draw_boxes(
+                &mut img,
+                &projector,
+                &mut boxes,
+                |iso_box, face, along, _| {
+                    let speckle = rng.gen_range(0..10);
+                    match iso_box.part {
+                        SUPPORT => {
+                            if speckle == 0 {
+                                base
+                            } else {
+                                dark
+                            }
+                        }
+                        DECK if face == Face::Top => {
+                            // Planks run across the deck, so seams follow the deck axis.
+                            let position = along / plank;
+                            if position.fract() < 0.15 {
+                                dark
+                            } else {
+                                let grain = stone_variation(position.floor(), 0.0);
+                                mix_color(base, light, grain * 0.7)
+                            }
+                        }
+                        _ => match speckle {
+                            0 => dark,
+                            1 => light,
+                            _ => base,
+                        },
+                    }
+                },
+            );
+        }
+        "stone" => {
+            let hexes = require_field(config.bridge_stone_colors.clone(), "bridge_stone_colors")?;
+            let palette = [
+                parse_hex_color(&hexes[0])?,
+                parse_hex_color(&hexes[1])?,
+                parse_hex_color(&hexes[2])?,
+            ];
+            let course =
+                require_field(config.bridge_course_height, "bridge_course_height")?.max(2) as f32;
+            let coursing = StoneCoursing::new(course, palette);
+            let mut boxes = stone_boxes(&deck, lands_low, lands_high);
+            // Abutments leave no room for the arch on the landing side.
+            let arch_start = if lands_low { 0.4 } else { 0.1 };
+            let arch_end = if lands_high { 0.6 } else { 0.9 };
+            let crown = deck_height * 0.75;
+            let side_face = if along_v { Face::Right } else { Face::Left };
+            draw_boxes(
+                &mut img,
+                &projector,
+                &mut boxes,
+                |iso_box, face, along, z| {
+                    if iso_box.part == DECK && face == side_face {
+                        let t = along / unit;
+                        let half_span = (arch_end - arch_start) * 0.5;
+                        let offset = (t - (arch_start + arch_end) * 0.5) / half_span;
+                        if offset.abs() < 1.0 {
+                            let opening = crown * (1.0 - offset * offset).sqrt();
+                            if z < opening - 2.0 {
+                                return Rgba([0, 0, 0, 0]);
+                            }
+                            if z < opening + 2.0 {
+                                // Voussoir ring around the arch opening.
+                                return palette[2];
+                            }
+                        }
+                    }
+                    coursing.color_at(&mut rng, face, along, z)
+                },
+            );
+        }
+        other => return Err(format!("Unknown bridge material: {other}")),
+    }
+    Ok(img)
+}
+
+struct Deck {
+    along_v: bool,
+    half_width: f32,
+    height: f32,
+    rail_height: f32,
+}
+
+impl Deck {
+    /// Box from `start` to `end` along the deck axis and `across` (low, high) across it.
+    fn span(&self, start: f32, end: f32, across: (f32, f32), z: (f32, f32), part: u8) -> IsoBox {
+        if self.along_v {
+            IsoBox::new([across.0, start, z.0], [across.1, end, z.1], part)
+        } else {
+            IsoBox::new([start, across.0, z.0], [end, across.1, z.1], part)
+        }
+    }
+
+    fn sides(&self, inset: f32, thickness: f32) -> [(f32, f32); 2] {
+        let low = 0.5 - self.half_width + inset;
+        let high = 0.5 + self.half_width - inset;
+        [(low, low + thickness), (high - thickness, high)]
+    }
+}
+
+fn wood_boxes(deck: &Deck, lands_low: bool, lands_high: bool) -> Vec<IsoBox> {
+    let thickness = (deck.height * 0.3).max(3.0);
+    let top = deck.height;
+    let mut boxes = vec![deck.span(
+        0.0,
+        1.0,
+        (0.5 - deck.half_width, 0.5 + deck.half_width),
+        (top - thickness, top),
+        DECK,
+    )];
+    for side in deck.sides(0.02, 0.05) {
+        for center in [0.25, 0.75] {
+            boxes.push(deck.span(
+                center - 0.03,
+                center + 0.03,
+                side,
+                (0.0, top - thickness),
+                SUPPORT,
+            ));
+        }
+        for center in [0.0, 0.5, 1.0] {
+            let (start, end) = ((center - 0.025_f32).max(0.0), (center + 0.025_f32).min(1.0));
+            boxes.push(deck.span(start, end, side, (top, top + deck.rail_height), RAILING));
+        }
+        let rail = (top + deck.rail_height - 3.0, top + deck.rail_height);
+        push_segments(&mut boxes, deck, side, rail, RAILING);
+    }
+    let across = (0.5 - deck.half_width, 0.5 + deck.half_width);
+    if lands_low {
+        boxes.push(deck.span(0.0, 0.12, across, (0.0, top - thickness), SUPPORT));
+    }
+    if lands_high {
+        boxes.push(deck.span(0.88, 1.0, across, (0.0, top - thickness), SUPPORT));
+    }
+    boxes
+}
+
+fn stone_boxes(deck: &Deck, lands_low: bool, lands_high: bool) -> Vec<IsoBox> {
+    let top = deck.height;
+    let across = (0.5 - deck.half_width, 0.5 + deck.half_width);
+    let mut boxes = vec![deck.span(0.0, 1.0, across, (0.0, top), DECK)];
+    for side in deck.sides(0.0, 0.06) {
+        push_segments(
+            &mut boxes,
+            deck,
+            side,
+            (top, top + deck.rail_height),
+            RAILING,
+        );
+        // Landing pillars cap the parapets where the bridge meets the shore.
+        let pillar = (top, top + deck.rail_height + 4.0);
+        let wide = (side.0 - 0.01, side.1 + 0.01);
+        if lands_low {
+            boxes.push(deck.span(0.0, 0.08, wide, pillar, RAILING));
+        }
+        if lands_high {
+            boxes.push(deck.span(0.92, 1.0, wide, pillar, RAILING));
+        }
+    }
+    boxes
+}
+
+/// Long rails are split into short boxes so back-to-front ordering stays correct next to
+/// the posts on the opposite side.
+fn push_segments(boxes: &mut Vec<IsoBox>, deck: &Deck, side: (f32, f32), z: (f32, f32), part: u8) {
+    for segment in 0..RAIL_SEGMENTS {
+        let start = segment as f32 / RAIL_SEGMENTS as f32;
+        let end = (segment + 1) as f32 / RAIL_SEGMENTS as f32;
+        boxes.push(deck.span(start, end, side, z, part));
+    }
+}
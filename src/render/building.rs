@@ -0,0 +1,660 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::block::shade_color;
+use crate::render::fence::stone_variation;
+use crate::render::rock::{dot, rasterize_triangle};
+use crate::render::tree::{encode_normal, project_raw};
+use crate::render::util::{mix_color, parse_hex_color};
+use crate::tree::{cross, Vec3};
+
+pub const ROOF_STYLES: [&str; 3] = ["gable", "hip", "flat"];
+pub const WALL_MATERIALS: [&str; 3] = ["plaster", "timber", "brick"];
+
+const EAVE_OVERHANG: f32 = 0.12;
+const PARAPET_HEIGHT: f32 = 0.12;
+const PARAPET_WIDTH: f32 = 0.08;
+const STOREY_HEIGHT: f32 = 0.75;
+const FRAME: f32 = 0.025;
+
+type Sprite = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+/// One building variant. The footprint and wall height come from the config so every sprite
+/// in a sheet shares one scale.
+#[derive(Clone, Copy)]
+pub struct BuildingStyle<'a> {
+    pub roof: &'a str,
+    pub material: &'a str,
+}
+
+/// Ground extent in pixels of the configured building footprint and the pixel at its center,
+/// in the same form as `decoration_layout`.
+pub fn building_layout(
+    config: &TileConfig,
+    sprite_width: u32,
+    sprite_height: u32,
+) -> Result<([u32; 2], [u32; 2]), String> {
+    let view = BuildingView::from_config(config, sprite_width, sprite_height)?;
+    let center = view.project(Vec3::new(view.width * 0.5, view.depth * 0.5, 0.0));
+    let extent = (view.scale * (view.width + view.depth)).round() as u32;
+    Ok((
+        [extent, extent / 2],
+        [center.0.round() as u32, center.1.round() as u32],
+    ))
+}
+
+pub fn render_building_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    style: BuildingStyle,
+) -> Result<Sprite, String> {
+    let wall_hexes = match style.material {
+        "plaster" => require_field(
+            config.building_plaster_colors.clone(),
+            "building_plaster_colors",
+        )?,
+        "timber" => require_field(
+            config.building_timber_colors.clone(),
+            "building_timber_colors",
+        )?,
+        "brick" => require_field(
+            config.building_brick_colors.clone(),
+            "building_brick_colors",
+        )?,
+        other => return Err(format!("Unknown building wall material: {other}")),
+    };
+    let wall = parse_palette(&wall_hexes)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let roof_palettes = require_field(config.building_roof_colors.clone(), "building_roof_colors")?;
+    if roof_palettes.is_empty() {
+        return Err("building_roof_colors must list at least one palette".to_string());
+    }
+    let roof = parse_palette(&roof_palettes[rng.gen_range(0..roof_palettes.len())])?;
+    let trim = parse_palette(&require_field(
+        config.building_window_colors.clone(),
+        "building_window_colors",
+    )?)?;
+    let raster = BuildingRaster::generate(sprite_width, sprite_height, config, style.roof)?;
+
+    // The door goes on one of the two street-facing walls.
+    let door_face = rng.gen_range(0..2);
+    let door_length = if door_face == 0 {
+        raster.view.depth
+    } else {
+        raster.view.width
+    };
+    let openings = Openings {
+        door_face,
+        door_tile: rng.gen_range(0..door_length as u32) as f32,
+        storey: raster.wall_height / (raster.wall_height / STOREY_HEIGHT).round().max(1.0),
+        wall_height: raster.wall_height,
+    };
+    let light = Vec3::new(-0.5, 0.3, 0.8).normalized();
+
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    for (idx, face) in raster.faces.iter().enumerate() {
+        let Some(face) = face.map(|index| &raster.surfaces[index]) else {
+            continue;
+        };
+        let x = idx as u32 % sprite_width;
+        let y = idx as u32 / sprite_width;
+        let point = raster.view.unproject(x as f32 + 0.5, y as f32 + 0.5, face);
+        let offset = point - face.origin;
+        let (along, up) = (dot(offset, face.along), dot(offset, face.up));
+        let color = match face.kind {
+            FaceKind::Facade(index) => openings
+                .color_at(index, along, up, trim)
+                .unwrap_or_else(|| wall_color(&mut rng, style.material, wall, along, up)),
+            FaceKind::Plain => wall_color(&mut rng, style.material, wall, along, up),
+            FaceKind::Roof => shingle_color(along, up, roof),
+            FaceKind::Deck => match rng.gen_range(0..8) {
+                0 => roof[0],
+                1 => roof[2],
+                _ => roof[1],
+            },
+            FaceKind::Coping => {
+                if (along / 0.25).fract() < 0.08 {
+                    wall[2]
+                } else {
+                    wall[0]
+                }
+            }
+        };
+        let lit = 0.5 + 0.5 * dot(face.normal, light);
+        img.put_pixel(x, y, shade_color(color, 0.55 + 0.45 * lit));
+    }
+    Ok(img)
+}
+
+pub fn render_building_mask_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    config: &TileConfig,
+    roof: &str,
+) -> Result<Sprite, String> {
+    let raster = BuildingRaster::generate(sprite_width, sprite_height, config, roof)?;
+    let mut mask = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+    for (idx, face) in raster.faces.iter().enumerate() {
+        if let Some(face) = face {
+            let x = idx as u32 % sprite_width;
+            let y = idx as u32 / sprite_width;
+            mask.put_pixel(x, y, encode_normal(raster.surfaces[*face].normal));
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_palette(hexes: &[String; 3]) -> Result<[Rgba<u8>; 3], String> {
+    Ok([
+        parse_hex_color(&hexes[0])?,
+        parse_hex_color(&hexes[1])?,
+        parse_hex_color(&hexes[2])?,
+    ])
+}
+
+/// `palette` holds the light, base and dark colors of the wall material.
+fn wall_color(
+    rng: &mut StdRng,
+    material: &str,
+    palette: [Rgba<u8>; 3],
+    along: f32,
+    z: f32,
+) -> Rgba<u8> {
+    let [light, base, dark] = palette;
+    // A dark plinth grounds every wall.
+    if z < 0.05 {
+        return dark;
+    }
+    match material {
+        "timber" => {
+            // Posts every half tile, rails at the sill and under the eaves, light infill.
+            let post = (along * 2.0 - (along * 2.0).round()).abs() < 0.06;
+            let rail = (z - 0.08).abs() < 0.03 || (z % STOREY_HEIGHT - STOREY_HEIGHT).abs() < 0.03;
+            let panel = (along * 2.0).fract();
+            let brace = (panel - (z % STOREY_HEIGHT) / STOREY_HEIGHT).abs() < 0.06;
+            if post || rail || brace {
+                dark
+            } else if rng.gen_range(0..12) == 0 {
+                base
+            } else {
+                light
+            }
+        }
+        "brick" => {
+            let row = (z / 0.06).floor();
+            let stagger = if row as i32 % 2 == 0 { 0.0 } else { 0.5 };
+            let column = along / 0.16 + stagger;
+            if (z / 0.06).fract() < 0.18 || column.fract() < 0.1 {
+                light
+            } else {
+                mix_color(base, dark, stone_variation(row, column.floor()) * 0.6)
+            }
+        }
+        _ => match rng.gen_range(0..14) {
+            0 => light,
+            1 => dark,
+            _ => mix_color(base, light, (z * 3.0).sin() * 0.05 + 0.05),
+        },
+    }
+}
+
+/// Overlapping shingle rows climbing from the eave; `up` is the distance up the slope.
+fn shingle_color(along: f32, up: f32, palette: [Rgba<u8>; 3]) -> Rgba<u8> {
+    let [light, base, dark] = palette;
+    // Fascia board along the eave.
+    if up < 0.03 {
+        return dark;
+    }
+    let row = (up / 0.08).floor();
+    let stagger = if row as i32 % 2 == 0 { 0.0 } else { 0.5 };
+    let column = along / 0.12 + stagger;
+    if (up / 0.08).fract() < 0.15 {
+        return dark;
+    }
+    if column.fract() < 0.08 {
+        return mix_color(base, dark, 0.6);
+    }
+    mix_color(base, light, stone_variation(row, column.floor()) * 0.5)
+}
+
+struct Openings {
+    door_face: usize,
+    door_tile: f32,
+    storey: f32,
+    wall_height: f32,
+}
+
+impl Openings {
+    /// Window or door color at `along`/`z` on facade `face`, or `None` for bare wall. Every
+    /// tile of wall gets one window per storey, except the ground floor door tile.
+    fn color_at(&self, face: usize, along: f32, z: f32, trim: [Rgba<u8>; 3]) -> Option<Rgba<u8>> {
+        let [glass, frame, door] = trim;
+        let tile = along.floor();
+        let offset = along - tile - 0.5;
+        let storey = (z / self.storey).floor();
+        if z >= self.wall_height {
+            return None;
+        }
+        if face == self.door_face && tile == self.door_tile && storey == 0.0 {
+            let (half, top) = (0.15, (self.storey * 0.7).min(0.6));
+            if offset.abs() > half + FRAME || z > top + FRAME {
+                return None;
+            }
+            if offset.abs() > half || z > top {
+                return Some(frame);
+            }
+            // Vertical planks with a handle on one side.
+            if ((offset + half) / 0.06).fract() < 0.15 {
+                return Some(mix_color(door, Rgba([0, 0, 0, 255]), 0.35));
+            }
+            if (offset - half * 0.6).abs() < FRAME && (z - top * 0.5).abs() < FRAME {
+                return Some(frame);
+            }
+            return Some(door);
+        }
+        let bottom = storey * self.storey + self.storey * 0.35;
+        let top = bottom + self.storey * 0.4;
+        let half = 0.14;
+        if (z - bottom).abs() < FRAME && offset.abs() < half + FRAME * 2.0 {
+            // The sill sticks out past the frame.
+            return Some(mix_color(frame, Rgba([0, 0, 0, 255]), 0.3));
+        }
+        if offset.abs() > half + FRAME || z < bottom || z > top + FRAME {
+            return None;
+        }
+        let mullion = offset.abs() < FRAME * 0.5 || (z - (bottom + top) * 0.5).abs() < FRAME * 0.5;
+        if offset.abs() > half || z > top || mullion {
+            return Some(frame);
+        }
+        if ((offset + z) * 6.0).fract() < 0.12 {
+            return Some(mix_color(glass, Rgba([255, 255, 255, 255]), 0.5));
+        }
+        Some(glass)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FaceKind {
+    /// Outer wall with windows and doors; the index tells the two street-facing walls apart.
+    Facade(usize),
+    Plain,
+    Roof,
+    Deck,
+    Coping,
+}
+
+/// Flat polygon of the building model with the axes its texture is laid out along.
+struct Face {
+    triangles: Vec<[Vec3; 3]>,
+    normal: Vec3,
+    origin: Vec3,
+    along: Vec3,
+    up: Vec3,
+    kind: FaceKind,
+}
+
+impl Face {
+    fn new(corners: &[Vec3], normal: Vec3, along: Vec3, up: Vec3, kind: FaceKind) -> Self {
+        let triangles = (1..corners.len() - 1)
+            .map(|index| [corners[0], corners[index], corners[index + 1]])
+            .collect();
+        Self {
+            triangles,
+            normal,
+            origin: corners[0],
+            along,
+            up,
+            kind,
+        }
+    }
+
+    /// Vertical face; texture rows run along `along` and up the wall from z = 0.
+    fn upright(corners: &[Vec3], normal: Vec3, along: Vec3, kind: FaceKind) -> Self {
+        let mut face = Self::new(corners, normal, along, Vec3::new(0.0, 0.0, 1.0), kind);
+        face.origin.z = 0.0;
+        face
+    }
+
+    /// Roof plane whose first corner lies on the eave; texture rows climb the slope.
+    fn sloped(corners: &[Vec3]) -> Self {
+        let mut normal = cross(corners[1] - corners[0], corners[2] - corners[0]).normalized();
+        if normal.z < 0.0 {
+            normal = normal * -1.0;
+        }
+        let up = (Vec3::new(0.0, 0.0, 1.0) - normal * normal.z).normalized();
+        let along = cross(normal, up).normalized();
+        Self::new(corners, normal, along, up, FaceKind::Roof)
+    }
+}
+
+/// Maps building space (tiles along x and y, height in tiles) to sprite pixels at the scale of
+/// `building_tile_width` ground tiles. The footprint is centered with its nearest ground corner
+/// on the bottom row.
+struct BuildingView {
+    width: f32,
+    depth: f32,
+    scale: f32,
+    origin_x: f32,
+    origin_y: f32,
+}
+
+impl BuildingView {
+    fn from_config(
+        config: &TileConfig,
+        sprite_width: u32,
+        sprite_height: u32,
+    ) -> Result<Self, String> {
+        let footprint = require_field(config.building_footprint, "building_footprint")?;
+        let tile_width = require_field(config.building_tile_width, "building_tile_width")?;
+        let width = footprint[0].max(1) as f32;
+        let depth = footprint[1].max(1) as f32;
+        let scale = tile_width.max(2) as f32 * 0.5;
+        Ok(Self {
+            width,
+            depth,
+            scale,
+            origin_x: sprite_width.saturating_sub(1) as f32 * 0.5 - (width - depth) * 0.5 * scale,
+            origin_y: sprite_height.saturating_sub(1) as f32 - (width + depth) * 0.5 * scale,
+        })
+    }
+
+    fn project(&self, point: Vec3) -> (f32, f32) {
+        let (raw_x, raw_y) = project_raw(point);
+        (
+            raw_x * self.scale + self.origin_x,
+            raw_y * self.scale + self.origin_y,
+        )
+    }
+
+    /// Point on `face` seen at pixel (`x`, `y`), found by casting the view ray onto its plane.
+    fn unproject(&self, x: f32, y: f32, face: &Face) -> Vec3 {
+        let raw_x = (x - self.origin_x) / self.scale;
+        let raw_y = (y - self.origin_y) / self.scale;
+        let base = Vec3::new(raw_x * 0.5 + raw_y, raw_y - raw_x * 0.5, 0.0);
+        let view = Vec3::new(1.0, 1.0, 1.0);
+        let t = dot(face.normal, face.triangles[0][0] - base) / dot(face.normal, view);
+        base + view * t
+    }
+}
+
+/// Per-pixel visible face of a building model, shared by the color and normal mask renderers.
+struct BuildingRaster {
+    faces: Vec<Option<usize>>,
+    surfaces: Vec<Face>,
+    view: BuildingView,
+    wall_height: f32,
+}
+
+impl BuildingRaster {
+    fn generate(
+        sprite_width: u32,
+        sprite_height: u32,
+        config: &TileConfig,
+        roof: &str,
+    ) -> Result<Self, String> {
+        if config.name != "building" {
+            return Err(format!("Unknown tile name: {}", config.name));
+        }
+        let wall_height = require_field(config.building_wall_height, "building_wall_height")?;
+        let pitch = require_field(config.building_roof_pitch, "building_roof_pitch")?;
+        let view = BuildingView::from_config(config, sprite_width, sprite_height)?;
+        let surfaces = building_faces(view.width, view.depth, wall_height, pitch, roof)?;
+
+        let len = (sprite_width * sprite_height) as usize;
+        let mut faces = vec![None; len];
+        let mut depth = vec![f32::NEG_INFINITY; len];
+        let project = |point: Vec3| view.project(point);
+        for (index, face) in surfaces.iter().enumerate() {
+            if dot(face.normal, Vec3::new(1.0, 1.0, 1.0)) <= 0.0 {
+                continue;
+            }
+            for triangle in &face.triangles {
+                let outside =
+                    |(x, y): (f32, f32)| x < -0.5 || x > sprite_width as f32 + 0.5 || y < -0.5;
+                if triangle.iter().any(|point| outside(project(*point))) {
+                    return Err(format!(
+                        "A {}x{} {roof} roof building does not fit a \
+                         {sprite_width}x{sprite_height} sprite",
+                        view.width, view.depth
+                    ));
+                }
+                rasterize_triangle(
+                    *triangle,
+                    &project,
+                    sprite_width,
+                    sprite_height,
+                    |idx, depth_value| {
+                        if depth_value > depth[idx] {
+                            depth[idx] = depth_value;
+                            faces[idx] = Some(index);
+                        }
+                    },
+                );
+            }
+        }
+        Ok(Self {
+            faces,
+            surfaces,
+            view,
+            wall_height,
+        })
+    }
+}
+
+/// Walls and roof of a `width` by `depth` tile building. Only the walls facing the viewer are
+/// modeled; the roof overhangs the walls by `EAVE_OVERHANG` on every side.
+fn building_faces(
+    width: f32,
+    depth: f32,
+    wall_height: f32,
+    pitch: f32,
+    roof: &str,
+) -> Result<Vec<Face>, String> {
+    let v = Vec3::new;
+    let (x_axis, y_axis) = (v(1.0, 0.0, 0.0), v(0.0, 1.0, 0.0));
+    // Pitched roofs pass through the wall tops, so the walls stop just short of them.
+    let top = if roof == "flat" {
+        wall_height + PARAPET_HEIGHT
+    } else {
+        wall_height - 0.01
+    };
+    let mut faces = vec![
+        Face::upright(
+            &[
+                v(width, 0.0, 0.0),
+                v(width, depth, 0.0),
+                v(width, depth, top),
+                v(width, 0.0, top),
+            ],
+            x_axis,
+            y_axis,
+            FaceKind::Facade(0),
+        ),
+        Face::upright(
+            &[
+                v(0.0, depth, 0.0),
+                v(width, depth, 0.0),
+                v(width, depth, top),
+                v(0.0, depth, top),
+            ],
+            y_axis,
+            x_axis,
+            FaceKind::Facade(1),
+        ),
+    ];
+
+    if roof == "flat" {
+        let (inner, rim) = (PARAPET_WIDTH, top);
+        let (far_x, far_y) = (width - inner, depth - inner);
+        faces.push(Face::new(
+            &[
+                v(inner, inner, wall_height),
+                v(far_x, inner, wall_height),
+                v(far_x, far_y, wall_height),
+                v(inner, far_y, wall_height),
+            ],
+            v(0.0, 0.0, 1.0),
+            x_axis,
+            y_axis,
+            FaceKind::Deck,
+        ));
+        // Inner sides of the parapet on the far walls face the viewer.
+        faces.push(Face::upright(
+            &[
+                v(inner, inner, wall_height),
+                v(inner, far_y, wall_height),
+                v(inner, far_y, rim),
+                v(inner, inner, rim),
+            ],
+            x_axis,
+            y_axis,
+            FaceKind::Plain,
+        ));
+        faces.push(Face::upright(
+            &[
+                v(inner, inner, wall_height),
+                v(far_x, inner, wall_height),
+                v(far_x, inner, rim),
+                v(inner, inner, rim),
+            ],
+            y_axis,
+            x_axis,
+            FaceKind::Plain,
+        ));
+        for (low, high) in [
+            ((0.0, 0.0), (width, inner)),
+            ((0.0, far_y), (width, depth)),
+            ((0.0, inner), (inner, far_y)),
+            ((far_x, inner), (width, far_y)),
+        ] {
+            faces.push(Face::new(
+                &[
+                    v(low.0, low.1, rim),
+                    v(high.0, low.1, rim),
+                    v(high.0, high.1, rim),
+                    v(low.0, high.1, rim),
+                ],
+                v(0.0, 0.0, 1.0),
+                if high.0 - low.0 > high.1 - low.1 {
+                    x_axis
+                } else {
+                    y_axis
+                },
+                v(0.0, 0.0, 1.0),
+                FaceKind::Coping,
+            ));
+        }
+        return Ok(faces);
+    }
+
+    // The ridge runs along the longer side. Roof corners are built with `a` along the ridge
+    // and `b` across it, then swapped into x and y when the building is deeper than wide.
+    let along_x = width >= depth;
+    let (length, span) = if along_x {
+        (width, depth)
+    } else {
+        (depth, width)
+    };
+    let at = |a: f32, b: f32, z: f32| if along_x { v(a, b, z) } else { v(b, a, z) };
+    let o = EAVE_OVERHANG;
+    let ridge = wall_height + pitch * span * 0.5;
+    let eave = wall_height - pitch * o;
+    let mid = span * 0.5;
+    let eaves = [
+        at(-o, -o, eave),
+        at(length + o, -o, eave),
+        at(length + o, span + o, eave),
+        at(-o, span + o, eave),
+    ];
+    match roof {
+        "gable" => {
+            let ridge_ends = [at(-o, mid, ridge), at(length + o, mid, ridge)];
+            faces.push(Face::sloped(&[
+                eaves[0],
+                eaves[1],
+                ridge_ends[1],
+                ridge_ends[0],
+            ]));
+            faces.push(Face::sloped(&[
+                eaves[3],
+                eaves[2],
+                ridge_ends[1],
+                ridge_ends[0],
+            ]));
+            let (end_normal, end_along) = if along_x {
+                (x_axis, y_axis)
+            } else {
+                (y_axis, x_axis)
+            };
+            faces.push(Face::upright(
+                &[
+                    at(length, 0.0, top),
+                    at(length, span, top),
+                    at(length, mid, ridge),
+                ],
+                end_normal,
+                end_along,
+                FaceKind::Plain,
+            ));
+        }
+        "hip" => {
+            let ridge_ends = [at(mid, mid, ridge), at(length - mid, mid, ridge)];
+            faces.push(Face::sloped(&[
+                eaves[0],
+                eaves[1],
+                ridge_ends[1],
+                ridge_ends[0],
+            ]));
+            faces.push(Face::sloped(&[
+                eaves[3],
+                eaves[2],
+                ridge_ends[1],
+                ridge_ends[0],
+            ]));
+            faces.push(Face::sloped(&[eaves[0], eaves[3], ridge_ends[0]]));
+            faces.push(Face::sloped(&[eaves[1], eaves[2], ridge_ends[1]]));
+        }
+        other => return Err(format!("Unknown roof style: {other}")),
+    }
+    Ok(faces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn building_config(footprint: [u32; 2]) -> TileConfig {
+        TileConfig {
+            name: "building".to_string(),
+            building_footprint: Some(footprint),
+            building_tile_width: Some(64),
+            building_wall_height: Some(1.5),
+            building_roof_pitch: Some(0.8),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn layout_centers_a_non_square_footprint_on_the_bottom_row() {
+        // A 3x1 footprint at 32 pixels per half tile spans 4 half tiles across.
+        for footprint in [[3, 1], [1, 3]] {
+            let layout = building_layout(&building_config(footprint), 193, 160).unwrap();
+            assert_eq!(layout, ([128, 64], [96, 127]), "{footprint:?}");
+        }
+    }
+
+    #[test]
+    fn every_roof_style_fits_its_sprite() {
+        let config = building_config([3, 2]);
+        for roof in ROOF_STYLES {
+            let raster = BuildingRaster::generate(192, 256, &config, roof)
+                .unwrap_or_else(|e| panic!("{roof}: {e}"));
+            assert!(raster.faces.iter().any(Option::is_some), "{roof} drew nothing");
+        }
+    }
+}
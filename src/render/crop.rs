@@ -0,0 +1,249 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::decoration::{draw_stroke, fill_ellipse, fill_span};
+use crate::render::util::{mix_color, parse_hex_color};
+
+pub const CROP_KINDS: [&str; 3] = ["wheat", "corn", "cabbage"];
+pub const MIN_CROP_STAGES: u32 = 4;
+
+type Sprite = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+/// Renders a tile of crops at growth `stage` (0 is freshly sprouted, `crop_stages - 1` is
+/// ready to harvest). Plants stand on a `crop_rows` by `crop_rows` grid over the ground
+/// diamond at the bottom of the sprite, matching farmland ridges with the same count.
+pub fn render_crop_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    kind: &str,
+    stage: u32,
+) -> Result<Sprite, String> {
+    if config.name != "crop" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let stages = require_field(config.crop_stages, "crop_stages")?;
+    if stages < MIN_CROP_STAGES {
+        return Err(format!(
+            "crop_stages must be at least {MIN_CROP_STAGES}, got {stages}"
+        ));
+    }
+    if stage >= stages {
+        return Err(format!(
+            "Crop stage {stage} is out of range for {stages} stages"
+        ));
+    }
+    let hexes = match kind {
+        "wheat" => require_field(config.crop_wheat_colors.clone(), "crop_wheat_colors")?,
+        "corn" => require_field(config.crop_corn_colors.clone(), "crop_corn_colors")?,
+        "cabbage" => require_field(config.crop_cabbage_colors.clone(), "crop_cabbage_colors")?,
+        other => return Err(format!("Unknown crop kind: {other}")),
+    };
+    let palette = [
+        parse_hex_color(&hexes[0])?,
+        parse_hex_color(&hexes[1])?,
+        parse_hex_color(&hexes[2])?,
+    ];
+    let rows = require_field(config.crop_rows, "crop_rows")?.max(1);
+    let growth = stage as f32 / (stages - 1) as f32;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    let width = sprite_width.saturating_sub(1) as f32;
+    let mid_y = sprite_height.saturating_sub(1) as f32 - width / 4.0;
+    let scale = width / 64.0;
+    let mut plants = Vec::new();
+    for row in 0..rows {
+        for column in 0..rows {
+            let jitter = 0.08 / rows as f32;
+            let u = (row as f32 + 0.5) / rows as f32 + rng.gen_range(-jitter..jitter);
+            let v = (column as f32 + 0.5) / rows as f32 + rng.gen_range(-jitter..jitter);
+            plants.push(((u + v) * width * 0.5, mid_y + (u - v) * width * 0.25));
+        }
+    }
+    // Back rows first so nearer plants overlap them.
+    plants.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let size = scale * 4.0 / rows as f32;
+    for (x, y) in plants {
+        let plant = Plant {
+            x,
+            y,
+            size: size * rng.gen_range(0.85..1.15),
+        };
+        if stage == 0 {
+            draw_sprout(&mut img, &mut rng, &plant, palette[0]);
+            continue;
+        }
+        match kind {
+            "wheat" => draw_wheat(&mut img, &mut rng, &plant, growth, palette),
+            "corn" => draw_corn(&mut img, &mut rng, &plant, growth, palette),
+            _ => draw_cabbage(&mut img, &mut rng, &plant, growth, palette),
+        }
+    }
+    Ok(img)
+}
+
+struct Plant {
+    x: f32,
+    y: f32,
+    /// Pixels per unit of the 64 pixel reference tile, shrunk for denser rows.
+    size: f32,
+}
+
+fn draw_sprout(img: &mut Sprite, rng: &mut StdRng, plant: &Plant, green: Rgba<u8>) {
+    for side in [-1.0, 1.0] {
+        let tip = (
+            plant.x + side * rng.gen_range(1.5..2.5) * plant.size,
+            plant.y - rng.gen_range(2.0..3.5) * plant.size,
+        );
+        draw_stroke(
+            img,
+            (plant.x, plant.y),
+            tip,
+            side * plant.size * 0.3,
+            0.5 * plant.size,
+            green,
+        );
+    }
+}
+
+/// `palette` holds the green stalk, ripe straw and grain head colors.
+fn draw_wheat(
+    img: &mut Sprite,
+    rng: &mut StdRng,
+    plant: &Plant,
+    growth: f32,
+    palette: [Rgba<u8>; 3],
+) {
+    let [green, straw, grain] = palette;
+    // Stalks stay green until the last stages, then ripen to straw.
+    let ripeness = ((growth - 0.5) * 2.0).clamp(0.0, 1.0);
+    let stalk_color = mix_color(green, straw, ripeness);
+    let height = (6.0 + 20.0 * growth) * plant.size;
+    for _ in 0..rng.gen_range(5..9) {
+        let root = (plant.x + rng.gen_range(-1.5..1.5) * plant.size, plant.y);
+        let lean = rng.gen_range(-2.5..2.5) * plant.size;
+        let tip = (root.0 + lean, root.1 - height * rng.gen_range(0.85..1.0));
+        let color = mix_color(stalk_color, Rgba([0, 0, 0, 255]), rng.gen_range(0.0..0.2));
+        draw_stroke(img, root, tip, lean * 0.3, 0.45 * plant.size, color);
+        if growth >= 0.5 {
+            // Ears start green and fill out with grain as the crop ripens.
+            let head = mix_color(green, grain, ripeness);
+            let length = (2.0 + 3.0 * growth) * plant.size;
+            for step in 0..=(length.ceil() as i32) {
+                let yy = tip.1 + step as f32 - length * 0.3;
+                let half = 0.7 * plant.size * (1.0 - (step as f32 / length - 0.5).abs());
+                fill_span(img, tip.0 - half, tip.0 + half, yy, head);
+            }
+        }
+    }
+}
+
+/// `palette` holds the stalk, leaf and ear/tassel colors.
+fn draw_corn(
+    img: &mut Sprite,
+    rng: &mut StdRng,
+    plant: &Plant,
+    growth: f32,
+    palette: [Rgba<u8>; 3],
+) {
+    let [stalk, leaf, ear] = palette;
+    let height = (8.0 + 36.0 * growth) * plant.size;
+    let top = (
+        plant.x + rng.gen_range(-1.0..1.0) * plant.size,
+        plant.y - height,
+    );
+    draw_stroke(img, (plant.x, plant.y), top, 0.0, 0.9 * plant.size, stalk);
+    let leaves = 2 + (growth * 5.0) as u32;
+    for index in 0..leaves {
+        let t = (index as f32 + 1.0) / (leaves as f32 + 1.0);
+        let base = (plant.x + (top.0 - plant.x) * t, plant.y - height * t);
+        let side = if index % 2 == 0 { -1.0 } else { 1.0 };
+        let reach = rng.gen_range(5.0..8.0) * plant.size * (0.5 + growth * 0.5);
+        let tip = (
+            base.0 + side * reach,
+            base.1 + rng.gen_range(-1.0..3.0) * plant.size,
+        );
+        draw_stroke(img, base, tip, -reach * 0.1, 0.8 * plant.size, leaf);
+    }
+    if growth >= 0.66 {
+        let ear_y = plant.y - height * 0.45;
+        let ear_x = plant.x + 1.5 * plant.size;
+        fill_ellipse(img, ear_x, ear_y, 1.2 * plant.size, 3.0 * plant.size, leaf);
+        fill_ellipse(
+            img,
+            ear_x,
+            ear_y - plant.size,
+            0.8 * plant.size,
+            2.0 * plant.size,
+            ear,
+        );
+    }
+    if growth >= 0.9 {
+        for side in [-1.0, 0.0, 1.0] {
+            let tassel = (top.0 + side * 2.0 * plant.size, top.1 - 3.0 * plant.size);
+            draw_stroke(img, top, tassel, side * plant.size, 0.4 * plant.size, ear);
+        }
+    }
+}
+
+/// `palette` holds the outer leaf, inner head and vein colors.
+fn draw_cabbage(
+    img: &mut Sprite,
+    rng: &mut StdRng,
+    plant: &Plant,
+    growth: f32,
+    palette: [Rgba<u8>; 3],
+) {
+    let [outer, inner, vein] = palette;
+    let radius = (3.0 + 4.5 * growth) * plant.size;
+    let center_y = plant.y - radius * 0.4;
+    let leaves = 5 + (growth * 4.0) as u32;
+    let phase = rng.gen_range(0.0..std::f32::consts::TAU);
+    let mut spread: Vec<f32> = (0..leaves)
+        .map(|index| phase + index as f32 / leaves as f32 * std::f32::consts::TAU)
+        .collect();
+    // Leaves behind the head are drawn first.
+    spread.sort_by(|a, b| a.sin().total_cmp(&b.sin()));
+    for angle in spread {
+        let (dx, dy) = (angle.cos() * radius * 0.7, angle.sin() * radius * 0.35);
+        let color = mix_color(
+            outer,
+            Rgba([0, 0, 0, 255]),
+            (0.2 - angle.sin() * 0.1).max(0.0),
+        );
+        fill_ellipse(
+            img,
+            plant.x + dx,
+            center_y + dy,
+            radius * 0.55,
+            radius * 0.35,
+            color,
+        );
+        draw_stroke(
+            img,
+            (plant.x, center_y),
+            (plant.x + dx * 1.2, center_y + dy * 1.2),
+            0.0,
+            0.3 * plant.size,
+            vein,
+        );
+    }
+    if growth >= 0.33 {
+        // The head firms up in the middle of the rosette.
+        let head = radius * 0.45 * growth;
+        fill_ellipse(img, plant.x, center_y - head * 0.4, head, head * 0.8, inner);
+        fill_ellipse(
+            img,
+            plant.x - head * 0.3,
+            center_y - head * 0.7,
+            head * 0.4,
+            head * 0.3,
+            mix_color(inner, Rgba([255, 255, 255, 255]), 0.25),
+        );
+    }
+}
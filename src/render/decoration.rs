@@ -0,0 +1,371 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::util::{mix_color, parse_hex_color};
+
+pub const DECORATION_KINDS: [&str; 5] = ["flower", "mushroom", "tuft", "reed", "log"];
+
+type Sprite = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+/// Ground footprint (width, height) and anchor pixel for a decoration kind. The footprint is
+/// an isometric ellipse centered on the anchor, which is where the sprite touches the ground.
+pub fn decoration_layout(
+    kind: &str,
+    sprite_width: u32,
+    sprite_height: u32,
+) -> Result<([u32; 2], [u32; 2]), String> {
+    let footprint_width = match kind {
+        "flower" => sprite_width / 4,
+        "mushroom" => sprite_width * 3 / 8,
+        "tuft" | "reed" => sprite_width / 2,
+        "log" => sprite_width * 3 / 4,
+        other => return Err(format!("Unknown decoration kind: {other}")),
+    };
+    let anchor = [sprite_width / 2, sprite_height - sprite_height / 4];
+    Ok(([footprint_width, footprint_width / 2], anchor))
+}
+
+pub fn render_decoration_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    kind: &str,
+) -> Result<Sprite, String> {
+    if config.name != "decoration" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let (footprint, anchor) = decoration_layout(kind, sprite_width, sprite_height)?;
+    let ground = Ground {
+        x: anchor[0] as f32,
+        y: anchor[1] as f32,
+        half_width: footprint[0] as f32 * 0.5,
+        half_height: footprint[1] as f32 * 0.5,
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    match kind {
+        "flower" => draw_flowers(&mut img, &mut rng, config, &ground)?,
+        "mushroom" => draw_mushrooms(&mut img, &mut rng, config, &ground)?,
+        "tuft" => draw_tuft(&mut img, &mut rng, config, &ground)?,
+        "reed" => draw_reeds(&mut img, &mut rng, config, &ground)?,
+        "log" => draw_log(&mut img, &mut rng, config, &ground)?,
+        _ => {}
+    }
+    Ok(img)
+}
+
+struct Ground {
+    x: f32,
+    y: f32,
+    half_width: f32,
+    half_height: f32,
+}
+
+impl Ground {
+    /// Random point inside the footprint ellipse.
+    fn point(&self, rng: &mut StdRng, spread: f32) -> (f32, f32) {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let radius = rng.gen_range(0.0_f32..1.0).sqrt() * spread;
+        (
+            self.x + angle.cos() * radius * self.half_width,
+            self.y + angle.sin() * radius * self.half_height,
+        )
+    }
+}
+
+fn draw_flowers(
+    img: &mut Sprite,
+    rng: &mut StdRng,
+    config: &TileConfig,
+    ground: &Ground,
+) -> Result<(), String> {
+    let petal_range = require_field(config.decoration_petal_count, "decoration_petal_count")?;
+    let petal_hexes = require_field(
+        config.decoration_flower_colors.clone(),
+        "decoration_flower_colors",
+    )?;
+    if petal_hexes.is_empty() {
+        return Err("decoration_flower_colors must not be empty".to_string());
+    }
+    let center = parse_hex_color(&require_field(
+        config.decoration_flower_center.clone(),
+        "decoration_flower_center",
+    )?)?;
+    let stem = parse_hex_color(&require_field(
+        config.decoration_stem_color.clone(),
+        "decoration_stem_color",
+    )?)?;
+    let petal = parse_hex_color(&petal_hexes[rng.gen_range(0..petal_hexes.len())])?;
+    let petal_count = rng
+        .gen_range(petal_range[0].min(petal_range[1])..=petal_range[1])
+        .max(3);
+
+    let scale = img.width() as f32 / 64.0;
+    let mut heads: Vec<(f32, f32, f32)> = (0..rng.gen_range(1..=3))
+        .map(|_| {
+            let (x, y) = ground.point(rng, 1.0);
+            (x, y, rng.gen_range(12.0..22.0) * scale)
+        })
+        .collect();
+    // Back flowers first so the front ones overlap them.
+    heads.sort_by(|a, b| a.1.total_cmp(&b.1));
+    for (x, y, height) in heads {
+        let sway = rng.gen_range(-3.0..3.0) * scale;
+        let (head_x, head_y) = (x + sway, y - height);
+        draw_stroke(img, (x, y), (head_x, head_y), sway * 0.5, 0.6 * scale, stem);
+        fill_ellipse(
+            img,
+            x + sway * 0.3 + 2.0 * scale,
+            y - height * 0.35,
+            2.0 * scale,
+            1.0 * scale,
+            stem,
+        );
+
+        let radius = rng.gen_range(3.0..4.5) * scale;
+        let phase = rng.gen_range(0.0..std::f32::consts::TAU);
+        let petal_shade = mix_color(petal, Rgba([0, 0, 0, 255]), 0.2);
+        for index in 0..petal_count {
+            let angle = phase + index as f32 / petal_count as f32 * std::f32::consts::TAU;
+            let (px, py) = (
+                head_x + angle.cos() * radius,
+                head_y + angle.sin() * radius * 0.7,
+            );
+            let color = if angle.sin() > 0.3 {
+                petal_shade
+            } else {
+                petal
+            };
+            fill_ellipse(img, px, py, radius * 0.7, radius * 0.5, color);
+        }
+        fill_ellipse(img, head_x, head_y, radius * 0.55, radius * 0.45, center);
+    }
+    Ok(())
+}
+
+fn draw_mushrooms(
+    img: &mut Sprite,
+    rng: &mut StdRng,
+    config: &TileConfig,
+    ground: &Ground,
+) -> Result<(), String> {
+    let hexes = require_field(
+        config.decoration_mushroom_colors.clone(),
+        "decoration_mushroom_colors",
+    )?;
+    let cap = parse_hex_color(&hexes[0])?;
+    let stalk = parse_hex_color(&hexes[1])?;
+    let spot = parse_hex_color(&hexes[2])?;
+    let cap_shadow = mix_color(cap, Rgba([0, 0, 0, 255]), 0.3);
+    let stalk_shadow = mix_color(stalk, Rgba([0, 0, 0, 255]), 0.2);
+
+    let scale = img.width() as f32 / 64.0;
+    let mut caps: Vec<(f32, f32, f32)> = (0..rng.gen_range(1..=3))
+        .map(|_| {
+            let (x, y) = ground.point(rng, 0.8);
+            (x, y, rng.gen_range(0.6..1.0))
+        })
+        .collect();
+    caps.sort_by(|a, b| a.1.total_cmp(&b.1));
+    for (x, y, size) in caps {
+        let height = 9.0 * size * scale;
+        let width = 2.0 * size * scale;
+        for step in 0..=(height.ceil() as i32) {
+            let yy = y - step as f32;
+            let flare = 1.0 + 0.3 * (1.0 - step as f32 / height).max(0.0);
+            fill_span(img, x - width * flare, x, yy, stalk_shadow);
+            fill_span(img, x, x + width * flare, yy, stalk);
+        }
+        let cap_radius = 6.0 * size * scale;
+        let cap_y = y - height;
+        for row in 0..=(cap_radius.ceil() as i32) {
+            let t = row as f32 / cap_radius;
+            let half = cap_radius * (1.0 - t * t).max(0.0).sqrt();
+            let yy = cap_y - row as f32 * 0.8;
+            fill_span(img, x - half, x + half, yy, cap);
+        }
+        // A darker underside gives the cap some volume.
+        fill_span(img, x - cap_radius, x + cap_radius, cap_y + 1.0, cap_shadow);
+        for _ in 0..rng.gen_range(2..5) {
+            let sx = x + rng.gen_range(-0.6..0.6) * cap_radius;
+            let sy = cap_y - rng.gen_range(0.2..0.7) * cap_radius * 0.8;
+            fill_ellipse(img, sx, sy, 0.9 * scale, 0.7 * scale, spot);
+        }
+    }
+    Ok(())
+}
+
+fn draw_tuft(
+    img: &mut Sprite,
+    rng: &mut StdRng,
+    config: &TileConfig,
+    ground: &Ground,
+) -> Result<(), String> {
+    let hexes = require_field(
+        config.decoration_grass_colors.clone(),
+        "decoration_grass_colors",
+    )?;
+    let dark = parse_hex_color(&hexes[0])?;
+    let light = parse_hex_color(&hexes[1])?;
+    let scale = img.width() as f32 / 64.0;
+    let blades = rng.gen_range(14..22);
+    let mut roots: Vec<(f32, f32)> = (0..blades).map(|_| ground.point(rng, 0.6)).collect();
+    roots.sort_by(|a, b| a.1.total_cmp(&b.1));
+    for (x, y) in roots {
+        let lean = (x - ground.x) / ground.half_width.max(1.0);
+        let height = rng.gen_range(8.0..16.0) * scale * (1.0 - lean.abs() * 0.4);
+        let tip = (
+            x + lean * 8.0 * scale + rng.gen_range(-2.0..2.0) * scale,
+            y - height,
+        );
+        let color = mix_color(dark, light, rng.gen_range(0.0..1.0));
+        draw_stroke(img, (x, y), tip, lean * 3.0 * scale, 0.9 * scale, color);
+    }
+    Ok(())
+}
+
+fn draw_reeds(
+    img: &mut Sprite,
+    rng: &mut StdRng,
+    config: &TileConfig,
+    ground: &Ground,
+) -> Result<(), String> {
+    let hexes = require_field(
+        config.decoration_reed_colors.clone(),
+        "decoration_reed_colors",
+    )?;
+    let stem = parse_hex_color(&hexes[0])?;
+    let head = parse_hex_color(&hexes[1])?;
+    let scale = img.width() as f32 / 64.0;
+    let mut stems: Vec<(f32, f32)> = (0..rng.gen_range(5..9))
+        .map(|_| ground.point(rng, 0.7))
+        .collect();
+    stems.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let head_shadow = mix_color(head, Rgba([0, 0, 0, 255]), 0.25);
+    for (x, y) in stems {
+        let height = rng.gen_range(26.0..44.0) * scale;
+        let bend = rng.gen_range(-4.0..4.0) * scale;
+        let tip = (x + bend, y - height);
+        let color = mix_color(stem, Rgba([0, 0, 0, 255]), rng.gen_range(0.0..0.25));
+        draw_stroke(img, (x, y), tip, bend * 0.5, 0.6 * scale, color);
+        // Only some reeds carry a cattail; the rest are bare leaves.
+        if rng.gen_bool(0.6) {
+            let head_length = rng.gen_range(5.0..8.0) * scale;
+            let (hx, hy) = (x + bend * 0.9, y - height * 0.8);
+            for step in 0..=(head_length.ceil() as i32) {
+                let yy = hy - step as f32 + head_length * 0.5;
+                fill_span(img, hx - 1.2 * scale, hx, yy, head_shadow);
+                fill_span(img, hx, hx + 1.2 * scale, yy, head);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn draw_log(
+    img: &mut Sprite,
+    rng: &mut StdRng,
+    config: &TileConfig,
+    ground: &Ground,
+) -> Result<(), String> {
+    let hexes = require_field(
+        config.decoration_log_colors.clone(),
+        "decoration_log_colors",
+    )?;
+    let bark = parse_hex_color(&hexes[0])?;
+    let bark_dark = parse_hex_color(&hexes[1])?;
+    let wood = parse_hex_color(&hexes[2])?;
+
+    // Logs lie along one of the two isometric ground axes.
+    let direction: f32 = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let half_length = ground.half_width * rng.gen_range(0.75..0.95);
+    let radius = ground.half_height * rng.gen_range(0.45..0.6);
+    let start = (
+        ground.x - half_length,
+        ground.y - half_length * 0.5 * direction - radius,
+    );
+    let end = (
+        ground.x + half_length,
+        ground.y + half_length * 0.5 * direction - radius,
+    );
+    let streaks: Vec<f32> = (0..rng.gen_range(3..6))
+        .map(|_| rng.gen_range(-0.8..0.8))
+        .collect();
+    let steps = (half_length * 4.0).ceil() as i32;
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let cx = start.0 + (end.0 - start.0) * t;
+        let cy = start.1 + (end.1 - start.1) * t;
+        for offset in -(radius.ceil() as i32)..=(radius.ceil() as i32) {
+            let across = offset as f32 / radius;
+            if across.abs() > 1.0 {
+                continue;
+            }
+            // Top of the log catches light, the underside falls into shadow.
+            let mut color = mix_color(bark, bark_dark, (across + 0.3).max(0.0));
+            if streaks.iter().any(|streak| (streak - across).abs() < 0.08) {
+                color = bark_dark;
+            }
+            put(img, cx, cy + offset as f32, color);
+        }
+    }
+    // The cut end faces the viewer on whichever side is lower on screen.
+    let cut = if direction > 0.0 { end } else { start };
+    let rings = mix_color(wood, bark_dark, 0.4);
+    fill_ellipse(img, cut.0, cut.1, radius * 0.7, radius, bark_dark);
+    fill_ellipse(img, cut.0, cut.1, radius * 0.55, radius * 0.85, wood);
+    fill_ellipse(img, cut.0, cut.1, radius * 0.25, radius * 0.4, rings);
+    fill_ellipse(img, cut.0, cut.1, radius * 0.12, radius * 0.2, wood);
+    Ok(())
+}
+
+/// Tapered quadratic stroke from `root` to `tip`, bowed sideways by `bow` pixels.
+pub(super) fn draw_stroke(
+    img: &mut Sprite,
+    root: (f32, f32),
+    tip: (f32, f32),
+    bow: f32,
+    half_width: f32,
+    color: Rgba<u8>,
+) {
+    let length = ((tip.0 - root.0).powi(2) + (tip.1 - root.1).powi(2)).sqrt();
+    let steps = (length * 2.0).ceil().max(1.0) as i32;
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let x = root.0 + (tip.0 - root.0) * t + bow * 4.0 * t * (1.0 - t);
+        let y = root.1 + (tip.1 - root.1) * t;
+        let width = (half_width * (1.0 - t * 0.7)).max(0.5);
+        fill_span(img, x - width, x + width, y, color);
+    }
+}
+
+pub(super) fn fill_ellipse(img: &mut Sprite, cx: f32, cy: f32, rx: f32, ry: f32, color: Rgba<u8>) {
+    let ry = ry.max(0.5);
+    for row in (-(ry.ceil() as i32))..=(ry.ceil() as i32) {
+        let t = row as f32 / ry;
+        if t.abs() > 1.0 {
+            continue;
+        }
+        let half = rx * (1.0 - t * t).sqrt();
+        fill_span(img, cx - half, cx + half, cy + row as f32, color);
+    }
+}
+
+pub(super) fn fill_span(img: &mut Sprite, from: f32, to: f32, y: f32, color: Rgba<u8>) {
+    let start = from.round() as i32;
+    let end = to.round().max(from.round()) as i32;
+    for x in start..=end {
+        put(img, x as f32, y, color);
+    }
+}
+
+fn put(img: &mut Sprite, x: f32, y: f32, color: Rgba<u8>) {
+    let (x, y) = (x.round() as i32, y.round() as i32);
+    if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+        img.put_pixel(x as u32, y as u32, color);
+    }
+}
@@ -3,7 +3,9 @@ use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 
 use crate::config::{require_field, TileConfig};
-use crate::render::util::{blit, draw_isometric_ground, parse_hex_color, random_tile_point};
+use crate::render::util::{
+    blit, draw_isometric_ground, parse_hex_color, random_tile_point, tile_ground_uv, NoiseField,
+};
 
 pub fn render_dirt_tile(
     sprite_width: u32,
@@ -23,8 +25,19 @@ pub fn render_dirt_tile(
     blit(&mut img, &base);
 
     let splotches = require_field(config.dirt_splotch_count, "dirt_splotch_count")?;
+    let noise = NoiseField::from_config(config.dirt_splotch_noise.as_ref(), seed)?;
+    let redraws = if noise.amount > 0.0 { 8 } else { 0 };
     for _ in 0..splotches {
-        let (cx, cy) = random_tile_point(&base, &mut rng);
+        // Points in the field's low areas are usually redrawn, so splotches gather in its highs.
+        let (mut cx, mut cy) = random_tile_point(&base, &mut rng);
+        for _ in 0..redraws {
+            let (u, v) = tile_ground_uv(cx as u32, cy as u32, sprite_width, sprite_height);
+            let keep = 1.0 - noise.amount * (1.0 - noise.sample(u, v));
+            if rng.gen_range(0.0..1.0) <= keep {
+                break;
+            }
+            (cx, cy) = random_tile_point(&base, &mut rng);
+        }
         let radius = rng.gen_range(3..=8);
         let shade = if rng.gen_bool(0.5) { palette[1] } else { palette[2] };
         draw_oval(&mut img, &base, cx, cy, radius * 2, radius, shade);
@@ -0,0 +1,114 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::util::{draw_isometric_ground, mix_color, parse_hex_color};
+use spriteforge_assets::{uv_from_xy, EDGE_E, EDGE_N, EDGE_S, EDGE_W};
+
+pub const FARMLAND_MOISTURE: [&str; 2] = ["dry", "wet"];
+
+/// Renders tilled soil with furrows running along `axis` (`EDGE_N | EDGE_S` or
+/// `EDGE_E | EDGE_W`). Ridges sit at `(i + 0.5) / farmland_furrow_count` across the tile so
+/// they continue into neighbors and line up with crop rows.
+pub fn render_farmland_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    axis: u8,
+    moisture: &str,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if config.name != "farmland" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let along_v = match axis {
+        mask if mask == EDGE_N | EDGE_S => true,
+        mask if mask == EDGE_E | EDGE_W => false,
+        other => {
+            return Err(format!(
+                "Farmland furrow axis must be one axis, got {other}"
+            ))
+        }
+    };
+    let hexes = match moisture {
+        "dry" => require_field(config.farmland_dry_colors.clone(), "farmland_dry_colors")?,
+        "wet" => require_field(config.farmland_wet_colors.clone(), "farmland_wet_colors")?,
+        other => return Err(format!("Unknown farmland moisture: {other}")),
+    };
+    let [ridge, soil, furrow] = [
+        parse_hex_color(&hexes[0])?,
+        parse_hex_color(&hexes[1])?,
+        parse_hex_color(&hexes[2])?,
+    ];
+    let puddle = if moisture == "wet" {
+        Some(parse_hex_color(&require_field(
+            config.farmland_puddle_color.clone(),
+            "farmland_puddle_color",
+        )?)?)
+    } else {
+        None
+    };
+    let furrows =
+        require_field(config.farmland_furrow_count, "farmland_furrow_count")?.max(1) as f32;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut base = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+    draw_isometric_ground(&mut base, sprite_width, sprite_height, soil);
+    // Puddles collect in furrow bottoms: (position along the furrow, furrow index, length).
+    let puddles: Vec<(f32, f32, f32)> = if puddle.is_some() {
+        (0..rng.gen_range(2..5))
+            .map(|_| {
+                (
+                    rng.gen_range(0.1..0.9),
+                    rng.gen_range(0..furrows as u32 + 1) as f32,
+                    rng.gen_range(0.05..0.15),
+                )
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    let width = sprite_width.max(1) as f32;
+    for (x, y, pixel) in base.enumerate_pixels() {
+        if pixel.0[3] == 0 {
+            continue;
+        }
+        let (u, v) = uv_from_xy(x as f32 / width, y as f32 / width);
+        let (across, along) = if along_v { (u, v) } else { (v, u) };
+        // 0 in a furrow bottom, 1 on a ridge top.
+        let phase = across * furrows;
+        let height = 0.5 - 0.5 * (phase * std::f32::consts::TAU).cos();
+        // The side of each ridge facing the viewer is lit, the far side shaded.
+        let slope = (phase * std::f32::consts::TAU).sin();
+        let mut color = if height > 0.5 {
+            mix_color(
+                soil,
+                ridge,
+                (height - 0.5) * 2.0 * (0.6 + 0.4 * slope.max(0.0)),
+            )
+        } else {
+            mix_color(furrow, soil, height * 2.0)
+        };
+        if let Some(puddle) = puddle {
+            let in_puddle = height < 0.12
+                && puddles.iter().any(|(start, index, length)| {
+                    (phase.round() - index).abs() < 0.5 && (along - start).abs() < *length
+                });
+            if in_puddle {
+                color = mix_color(puddle, color, 0.25);
+            }
+        }
+        // Clods break up the rows.
+        match rng.gen_range(0..40) {
+            0 => color = mix_color(color, furrow, 0.5),
+            1 => color = mix_color(color, ridge, 0.5),
+            _ => {}
+        }
+        img.put_pixel(x, y, color);
+    }
+    Ok(img)
+}
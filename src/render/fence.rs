@@ -0,0 +1,335 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::block::shade_color;
+use crate::render::util::{mix_color, parse_hex_color};
+use spriteforge_assets::{EDGE_E, EDGE_N, EDGE_S, EDGE_W};
+
+const RIGHT_FACE_SHADE: f32 = 0.75;
+const TOP_FACE_SHADE: f32 = 1.15;
+const POST_HALF_SIZE: f32 = 0.035;
+const RAIL_HALF_SIZE: f32 = 0.012;
+/// Rails sit at 1/(n+1) steps plus a tenth of the post height, so a tenth rail would clear
+/// the post tops.
+const MAX_FENCE_RAILS: u32 = 9;
+
+type Sprite = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+/// Renders a wooden fence whose rails run from the tile center toward every edge set in
+/// `connection_mask`. Posts stand on the center and on each connected edge so neighbors
+/// share them.
+pub fn render_fence_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    connection_mask: u8,
+) -> Result<Sprite, String> {
+    if config.name != "fence" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let height = require_field(config.fence_height, "fence_height")? as f32;
+    let rail_count = require_field(config.fence_rail_count, "fence_rail_count")?.max(1);
+    if rail_count > MAX_FENCE_RAILS {
+        return Err(format!(
+            "fence_rail_count {rail_count} puts rails above the posts; use at most {MAX_FENCE_RAILS}"
+        ));
+    }
+    let hexes = require_field(config.fence_colors.clone(), "fence_colors")?;
+    let palette = [
+        parse_hex_color(&hexes[0])?,
+        parse_hex_color(&hexes[1])?,
+        parse_hex_color(&hexes[2])?,
+    ];
+    if height + sprite_width as f32 / 2.0 > sprite_height as f32 {
+        return Err(format!(
+            "fence_height {height} does not fit in a {sprite_width}x{sprite_height} sprite"
+        ));
+    }
+
+    let mut boxes = vec![IsoBox::pillar(POST_HALF_SIZE, height)];
+    for edge in connected_edges(connection_mask) {
+        boxes.push(IsoBox::pillar(POST_HALF_SIZE, height).moved_to_edge(edge));
+        for rail in 1..=rail_count {
+            let top = height * (rail as f32 / (rail_count + 1) as f32 + 0.1);
+            let thickness = (height * 0.08).max(2.0);
+            boxes.push(IsoBox::arm(edge, RAIL_HALF_SIZE, top - thickness, top));
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    let projector = Projector::new(sprite_width, sprite_height);
+    draw_boxes(&mut img, &projector, &mut boxes, |_, _, _, _| {
+        // Wood grain is a light speckle between the base and darker tones.
+        match rng.gen_range(0..12) {
+            0 => palette[2],
+            1 => palette[0],
+            _ => palette[1],
+        }
+    });
+    Ok(img)
+}
+
+/// Renders a stone wall segment. The wall is a central pillar joined by a full-height arm
+/// for every edge set in `connection_mask`; stones line up across tiles because the coursing
+/// is laid out in tile space.
+pub fn render_wall_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    connection_mask: u8,
+) -> Result<Sprite, String> {
+    if config.name != "wall" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let height = require_field(config.wall_height, "wall_height")? as f32;
+    let half_thickness =
+        require_field(config.wall_thickness, "wall_thickness")?.clamp(0.02, 0.5) * 0.5;
+    let course = require_field(config.wall_course_height, "wall_course_height")?.max(2) as f32;
+    let hexes = require_field(config.wall_colors.clone(), "wall_colors")?;
+    let stone = parse_hex_color(&hexes[0])?;
+    let stone_light = parse_hex_color(&hexes[1])?;
+    let mortar = parse_hex_color(&hexes[2])?;
+    if height + sprite_width as f32 / 2.0 > sprite_height as f32 {
+        return Err(format!(
+            "wall_height {height} does not fit in a {sprite_width}x{sprite_height} sprite"
+        ));
+    }
+
+    let mut boxes = vec![IsoBox::pillar(half_thickness, height)];
+    for edge in connected_edges(connection_mask) {
+        boxes.push(IsoBox::arm(edge, half_thickness, 0.0, height));
+    }
+
+    let coursing = StoneCoursing::new(course, [stone, stone_light, mortar]);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    let projector = Projector::new(sprite_width, sprite_height);
+    draw_boxes(&mut img, &projector, &mut boxes, |_, face, along, z| {
+        coursing.color_at(&mut rng, face, along, z)
+    });
+    Ok(img)
+}
+
+pub(super) fn connected_edges(connection_mask: u8) -> Vec<u8> {
+    [EDGE_N, EDGE_E, EDGE_S, EDGE_W]
+        .into_iter()
+        .filter(|edge| connection_mask & edge != 0)
+        .collect()
+}
+
+/// Running-bond masonry laid out in tile space, shared by walls and stone bridges.
+pub(super) struct StoneCoursing {
+    course: f32,
+    stone_length: f32,
+    palette: [Rgba<u8>; 3],
+}
+
+impl StoneCoursing {
+    /// `palette` holds the stone, light stone and mortar colors.
+    pub(super) fn new(course: f32, palette: [Rgba<u8>; 3]) -> Self {
+        Self {
+            course,
+            stone_length: course * 2.2,
+            palette,
+        }
+    }
+
+    pub(super) fn color_at(&self, rng: &mut StdRng, face: Face, along: f32, z: f32) -> Rgba<u8> {
+        let [stone, stone_light, mortar] = self.palette;
+        if face == Face::Top {
+            // Cap stones run along the wall and only show their joints.
+            let joint = (along / self.stone_length).fract() < 0.04;
+            return if joint { mortar } else { stone_light };
+        }
+        let row = (z / self.course).floor();
+        let offset = if row as i32 % 2 == 0 {
+            0.0
+        } else {
+            self.stone_length * 0.5
+        };
+        let along = along + offset;
+        let column = (along / self.stone_length).floor();
+        if (z / self.course).fract() < 1.0 / self.course
+            || (along / self.stone_length).fract() < 1.5 / self.stone_length
+        {
+            return mortar;
+        }
+        let color = mix_color(stone, stone_light, stone_variation(row, column) * 0.6);
+        // Chipped spots differ per tile so repeated walls do not look stamped.
+        if rng.gen_bool(0.03) {
+            shade_color(color, 0.85)
+        } else {
+            color
+        }
+    }
+}
+
+/// Stable brightness for one stone so coursing matches between neighboring tiles.
+pub(super) fn stone_variation(row: f32, column: f32) -> f32 {
+    let mut hash = (row as i32 as u32).wrapping_mul(0x9e37_79b9)
+        ^ (column as i32 as u32).wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2c1b_3c6d);
+    hash ^= hash >> 12;
+    (hash % 1000) as f32 / 1000.0
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Face {
+    Top,
+    Left,
+    Right,
+}
+
+/// Maps tile space (u, v in 0..1 across the ground diamond, z in pixels) onto the sprite.
+pub(super) struct Projector {
+    pub(super) width: f32,
+    mid_y: f32,
+}
+
+impl Projector {
+    pub(super) fn new(sprite_width: u32, sprite_height: u32) -> Self {
+        let width = sprite_width.saturating_sub(1) as f32;
+        Self {
+            width,
+            mid_y: sprite_height.saturating_sub(1) as f32 - width / 4.0,
+        }
+    }
+
+    fn project(&self, u: f32, v: f32, z: f32) -> (f32, f32) {
+        (
+            (u + v) * self.width * 0.5,
+            self.mid_y + (u - v) * self.width * 0.25 - z,
+        )
+    }
+}
+
+/// Axis-aligned box in tile space. `part` lets callers color boxes differently and orders
+/// drawing: lower parts are painted first.
+#[derive(Clone, Copy)]
+pub(super) struct IsoBox {
+    pub(super) min: [f32; 3],
+    pub(super) max: [f32; 3],
+    pub(super) part: u8,
+}
+
+impl IsoBox {
+    pub(super) fn new(min: [f32; 3], max: [f32; 3], part: u8) -> Self {
+        Self { min, max, part }
+    }
+
+    pub(super) fn pillar(half_size: f32, height: f32) -> Self {
+        Self::new(
+            [0.5 - half_size, 0.5 - half_size, 0.0],
+            [0.5 + half_size, 0.5 + half_size, height],
+            0,
+        )
+    }
+
+    /// Box running from the tile center to the midpoint of `edge`.
+    pub(super) fn arm(edge: u8, half_size: f32, bottom: f32, top: f32) -> Self {
+        let (low, high) = (0.5 - half_size, 0.5 + half_size);
+        let (u, v) = match edge {
+            EDGE_N => ([low, high], [0.5, 1.0]),
+            EDGE_S => ([low, high], [0.0, 0.5]),
+            EDGE_E => ([0.5, 1.0], [low, high]),
+            _ => ([0.0, 0.5], [low, high]),
+        };
+        Self::new([u[0], v[0], bottom], [u[1], v[1], top], 0)
+    }
+
+    pub(super) fn moved_to_edge(mut self, edge: u8) -> Self {
+        let (du, dv) = match edge {
+            EDGE_N => (0.0, 0.5),
+            EDGE_S => (0.0, -0.5),
+            EDGE_E => (0.5, 0.0),
+            _ => (-0.5, 0.0),
+        };
+        self.min[0] += du;
+        self.max[0] += du;
+        self.min[1] += dv;
+        self.max[1] += dv;
+        self
+    }
+
+    /// Screen depth of the box center; larger values are closer to the viewer.
+    fn depth(&self) -> f32 {
+        (self.min[0] + self.max[0]) - (self.min[1] + self.max[1])
+    }
+}
+
+/// Paints boxes part by part, back to front within a part. Only the top, left (v = min) and
+/// right (u = max) faces face the viewer. `color_at` receives the box, the face, a horizontal
+/// coordinate along it in pixels and the height above ground; transparent colors are skipped.
+pub(super) fn draw_boxes<F>(
+    img: &mut Sprite,
+    projector: &Projector,
+    boxes: &mut [IsoBox],
+    mut color_at: F,
+) where
+    F: FnMut(&IsoBox, Face, f32, f32) -> Rgba<u8>,
+{
+    boxes.sort_by(|a, b| {
+        a.part
+            .cmp(&b.part)
+            .then(a.depth().total_cmp(&b.depth()))
+            .then(a.min[2].total_cmp(&b.min[2]))
+    });
+    let unit = projector.width * 0.5;
+    for iso_box in boxes.iter() {
+        let [u0, v0, z0] = iso_box.min;
+        let [u1, v1, z1] = iso_box.max;
+        let height_steps = ((z1 - z0) * 2.0).ceil().max(1.0) as i32;
+        let side = |from: f32, to: f32| (((to - from) * unit * 2.0).ceil().max(1.0)) as i32;
+
+        // Left face at v = v0, spanning u.
+        let steps = side(u0, u1);
+        for i in 0..=steps {
+            let u = u0 + (u1 - u0) * i as f32 / steps as f32;
+            for j in 0..=height_steps {
+                let z = z0 + (z1 - z0) * j as f32 / height_steps as f32;
+                let color = color_at(iso_box, Face::Left, u * unit, z);
+                plot(img, projector.project(u, v0, z), color);
+            }
+        }
+        // Right face at u = u1, spanning v.
+        let steps = side(v0, v1);
+        for i in 0..=steps {
+            let v = v0 + (v1 - v0) * i as f32 / steps as f32;
+            for j in 0..=height_steps {
+                let z = z0 + (z1 - z0) * j as f32 / height_steps as f32;
+                let color = color_at(iso_box, Face::Right, v * unit, z);
+                let color = shade_color(color, RIGHT_FACE_SHADE);
+                plot(img, projector.project(u1, v, z), color);
+            }
+        }
+        // Top face; `along` follows the longer side so cap joints cross the wall.
+        let (u_steps, v_steps) = (side(u0, u1), side(v0, v1));
+        for i in 0..=u_steps {
+            let u = u0 + (u1 - u0) * i as f32 / u_steps as f32;
+            for j in 0..=v_steps {
+                let v = v0 + (v1 - v0) * j as f32 / v_steps as f32;
+                let along = if u1 - u0 >= v1 - v0 { u } else { v } * unit;
+                let color = shade_color(color_at(iso_box, Face::Top, along, z1), TOP_FACE_SHADE);
+                plot(img, projector.project(u, v, z1), color);
+            }
+        }
+    }
+}
+
+fn plot(img: &mut Sprite, (x, y): (f32, f32), color: Rgba<u8>) {
+    if color.0[3] == 0 {
+        return;
+    }
+    let (x, y) = (x.round() as i32, y.round() as i32);
+    if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+        img.put_pixel(x as u32, y as u32, color);
+    }
+}
@@ -3,7 +3,9 @@ use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
 use crate::config::{require_field, TileConfig, TransitionOverrides};
-use crate::render::util::{blit, draw_isometric_ground, parse_hex_color};
+use crate::render::util::{
+    blit, draw_isometric_ground, parse_hex_color, tile_ground_uv, NoiseField,
+};
 use spriteforge_assets::edge_weight_for_mask;
 
 pub fn render_grass_tile(
@@ -17,15 +19,13 @@ pub fn render_grass_tile(
         return Err(format!("Unknown tile name: {}", config.name));
     }
     let mut rng = StdRng::seed_from_u64(seed);
-    let palette = grass_palette(config)?;
+    let blades = GrassBlades::from_config(config, seed)?;
     let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
     let mut base = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
-    draw_isometric_ground(&mut base, sprite_width, sprite_height, palette[0]);
+    draw_isometric_ground(&mut base, sprite_width, sprite_height, blades.palette[0]);
     blit(&mut img, &base);
 
-    let blade_min = require_field(config.blade_min, "blade_min")?;
-    let blade_max = require_field(config.blade_max, "blade_max")?;
-    add_grass_blades(&mut img, &base, &mut rng, &palette, blade_min, blade_max);
+    add_grass_blades(&mut img, &base, &mut rng, &blades);
     Ok(img)
 }
 
@@ -42,53 +42,40 @@ pub fn render_grass_transition_tile(
         return Err(format!("Unknown tile name: {}", config.name));
     }
     let mut rng = StdRng::seed_from_u64(seed);
-    let grass_palette = grass_palette(config)?;
+    let blades = GrassBlades::from_config(config, seed)?;
     let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
     let mut base = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
     draw_isometric_ground(&mut base, sprite_width, sprite_height, Rgba([0, 0, 0, 255]));
 
-    let blade_min = require_field(config.blade_min, "blade_min")?;
-    let blade_max = require_field(config.blade_max, "blade_max")?;
-    let mut density = require_field(config.transition_density, "transition_density")?
-        .clamp(0.0, 1.0);
-    let mut bias =
-        require_field(config.transition_bias, "transition_bias")?.clamp(0.0, 1.0);
-    let mut falloff = require_field(config.transition_falloff, "transition_falloff")?;
-    let mut edge_cutoff =
-        require_field(config.grass_edge_cutoff, "grass_edge_cutoff")?.clamp(0.0, 1.0);
-    let mut edge_gradient =
-        require_field(config.grass_edge_gradient, "grass_edge_gradient")?.max(0.0);
+    let mut edge = BladeFalloff {
+        density: require_field(config.transition_density, "transition_density")?
+            .clamp(0.0, 1.0),
+        bias: require_field(config.transition_bias, "transition_bias")?.clamp(0.0, 1.0),
+        falloff: require_field(config.transition_falloff, "transition_falloff")?,
+        transition_mask,
+        edge_cutoff: require_field(config.grass_edge_cutoff, "grass_edge_cutoff")?
+            .clamp(0.0, 1.0),
+        edge_gradient: require_field(config.grass_edge_gradient, "grass_edge_gradient")?
+            .max(0.0),
+    };
     if let Some(overrides) = overrides {
         if let Some(override_density) = overrides.density {
-            density = override_density.clamp(0.0, 1.0);
+            edge.density = override_density.clamp(0.0, 1.0);
         }
         if let Some(override_bias) = overrides.bias {
-            bias = override_bias.clamp(0.0, 1.0);
+            edge.bias = override_bias.clamp(0.0, 1.0);
         }
         if let Some(override_falloff) = overrides.falloff {
-            falloff = override_falloff;
+            edge.falloff = override_falloff;
         }
         if let Some(override_cutoff) = overrides.grass_edge_cutoff {
-            edge_cutoff = override_cutoff.clamp(0.0, 1.0);
+            edge.edge_cutoff = override_cutoff.clamp(0.0, 1.0);
         }
         if let Some(override_gradient) = overrides.grass_edge_gradient {
-            edge_gradient = override_gradient.max(0.0);
+            edge.edge_gradient = override_gradient.max(0.0);
         }
     }
-    add_grass_blades_weighted(
-        &mut img,
-        &base,
-        &mut rng,
-        &grass_palette,
-        blade_min,
-        blade_max,
-        density,
-        bias,
-        transition_mask,
-        edge_cutoff,
-        edge_gradient,
-        falloff,
-    );
+    add_grass_blades_weighted(&mut img, &base, &mut rng, &blades, &edge);
 
     Ok(img)
 }
@@ -97,44 +84,40 @@ pub fn add_grass_blades(
     img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
     base: &ImageBuffer<Rgba<u8>, Vec<u8>>,
     rng: &mut StdRng,
-    palette: &[Rgba<u8>; 4],
-    blade_min: i32,
-    blade_max: i32,
+    blades: &GrassBlades,
 ) {
-    let min_blade = blade_min.max(1);
-    let max_blade = blade_max.max(min_blade);
-    let shades = [palette[1], palette[2], palette[3]];
+    let (width, height) = base.dimensions();
 
     for (x, y, pixel) in base.enumerate_pixels() {
         if pixel.0[3] == 0 {
             continue;
         }
-        let length = rng.gen_range(min_blade..=max_blade);
-        let shade = shades[rng.gen_range(0..shades.len())];
-        for dy in 0..length {
-            put_pixel_safe(img, x as i32, y as i32 - dy, shade);
+        let (u, v) = tile_ground_uv(x, y, width, height);
+        if blades.density.amount > 0.0 && rng.gen_range(0.0..1.0) > blades.density(u, v) {
+            continue;
         }
+        blades.draw(img, rng, x, y, u, v);
     }
 }
 
+/// How a transition tile thins its blades toward the edges in `transition_mask`.
+pub struct BladeFalloff {
+    pub density: f32,
+    pub bias: f32,
+    pub falloff: f32,
+    pub transition_mask: u8,
+    pub edge_cutoff: f32,
+    pub edge_gradient: f32,
+}
+
 pub fn add_grass_blades_weighted(
     img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
     base: &ImageBuffer<Rgba<u8>, Vec<u8>>,
     rng: &mut StdRng,
-    palette: &[Rgba<u8>; 4],
-    blade_min: i32,
-    blade_max: i32,
-    density: f32,
-    bias: f32,
-    transition_mask: u8,
-    edge_cutoff: f32,
-    edge_gradient: f32,
-    falloff: f32,
+    blades: &GrassBlades,
+    edge: &BladeFalloff,
 ) {
-    let min_blade = blade_min.max(1);
-    let max_blade = blade_max.max(min_blade);
     let width = base.width().max(1) as f32;
-    let shades = [palette[1], palette[2], palette[3]];
 
     for (x, y, pixel) in base.enumerate_pixels() {
         if pixel.0[3] == 0 {
@@ -142,15 +125,69 @@ pub fn add_grass_blades_weighted(
         }
         let xf = x as f32 / width;
         let yf = y as f32 / width;
-        let edge_weight =
-            edge_weight_for_mask(transition_mask, xf, yf, edge_cutoff, edge_gradient)
-                .powf(falloff);
-        let prob = density * ((1.0 - bias) + bias * edge_weight);
+        let edge_weight = edge_weight_for_mask(
+            edge.transition_mask,
+            xf,
+            yf,
+            edge.edge_cutoff,
+            edge.edge_gradient,
+        )
+        .powf(edge.falloff);
+        let (u, v) = tile_ground_uv(x, y, base.width(), base.height());
+        let prob =
+            edge.density * ((1.0 - edge.bias) + edge.bias * edge_weight) * blades.density(u, v);
         if rng.gen_range(0.0..1.0) > prob {
             continue;
         }
-        let length = rng.gen_range(min_blade..=max_blade);
-        let shade = shades[rng.gen_range(0..shades.len())];
+        blades.draw(img, rng, x, y, u, v);
+    }
+}
+
+/// Blade lengths and colors, and the noise fields that thin blades into patches and group
+/// their shades into drifts.
+pub struct GrassBlades {
+    palette: [Rgba<u8>; 4],
+    min: i32,
+    max: i32,
+    density: NoiseField,
+    shade: NoiseField,
+}
+
+impl GrassBlades {
+    pub fn from_config(config: &TileConfig, seed: u64) -> Result<Self, String> {
+        let min = require_field(config.blade_min, "blade_min")?.max(1);
+        let max = require_field(config.blade_max, "blade_max")?.max(min);
+        Ok(Self {
+            palette: grass_palette(config)?,
+            min,
+            max,
+            density: NoiseField::from_config(config.grass_density_noise.as_ref(), seed)?,
+            shade: NoiseField::from_config(config.grass_shade_noise.as_ref(), seed)?,
+        })
+    }
+
+    /// Chance that a blade grows at ground point `(u, v)`.
+    fn density(&self, u: f32, v: f32) -> f32 {
+        1.0 - self.density.amount * (1.0 - self.density.sample(u, v))
+    }
+
+    fn draw(
+        &self,
+        img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
+        rng: &mut StdRng,
+        x: u32,
+        y: u32,
+        u: f32,
+        v: f32,
+    ) {
+        let length = rng.gen_range(self.min..=self.max);
+        let shade = if self.shade.amount > 0.0 {
+            let random = rng.gen_range(0.0..1.0);
+            let t = random + (self.shade.sample(u, v) - random) * self.shade.amount;
+            self.palette[1 + ((t * 3.0) as usize).min(2)]
+        } else {
+            self.palette[1 + rng.gen_range(0..3)]
+        };
         for dy in 0..length {
             put_pixel_safe(img, x as i32, y as i32 - dy, shade);
         }
@@ -0,0 +1,272 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig, TransitionOverrides};
+use crate::render::util::{
+    draw_isometric_ground, mix_color, parse_hex_color, random_tile_point, smoothstep,
+    transition_edge_settings,
+};
+use spriteforge_assets::{edge_weight_for_mask, uv_from_xy};
+
+const MASK_BASE_ALPHA: f32 = 96.0;
+
+pub fn render_ice_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    transition_mask: u8,
+    overrides: Option<&TransitionOverrides>,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    check_ice_name(config, transition_mask)?;
+    let palette = ice_palette(config)?;
+    let surface = IceSurface::generate(
+        sprite_width,
+        sprite_height,
+        seed,
+        config,
+        transition_mask,
+        overrides,
+    )?;
+
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    for (x, y, pixel) in surface.base.enumerate_pixels() {
+        if pixel.0[3] == 0 {
+            continue;
+        }
+        let idx = (y * sprite_width + x) as usize;
+        // Slightly deeper tone toward the front of the tile reads as thickness.
+        let depth = y as f32 / sprite_height.max(1) as f32;
+        let mut color = mix_color(palette[0], palette[1], depth * 0.35);
+        color = mix_color(color, palette[2], surface.streak[idx] * 0.8);
+        if surface.crack[idx] {
+            color = palette[1];
+        }
+        color.0[3] = (color.0[3] as f32 * surface.coverage[idx]).round() as u8;
+        img.put_pixel(x, y, color);
+    }
+    Ok(img)
+}
+
+/// Glint mask for the water foam material: alpha is strongest on specular streaks
+/// and cleared along cracks.
+pub fn render_ice_mask_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    seed: u64,
+    config: &TileConfig,
+    transition_mask: u8,
+    overrides: Option<&TransitionOverrides>,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    check_ice_name(config, transition_mask)?;
+    let surface = IceSurface::generate(
+        sprite_width,
+        sprite_height,
+        seed,
+        config,
+        transition_mask,
+        overrides,
+    )?;
+
+    let mut mask = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+    for (x, y, pixel) in surface.base.enumerate_pixels() {
+        if pixel.0[3] == 0 {
+            continue;
+        }
+        let idx = (y * sprite_width + x) as usize;
+        if surface.crack[idx] {
+            continue;
+        }
+        let glint = MASK_BASE_ALPHA + (255.0 - MASK_BASE_ALPHA) * surface.streak[idx];
+        let alpha = (glint * surface.coverage[idx]).round() as u8;
+        mask.put_pixel(x, y, Rgba([255, 255, 255, alpha]));
+    }
+    Ok(mask)
+}
+
+struct IceSurface {
+    base: ImageBuffer<Rgba<u8>, Vec<u8>>,
+    streak: Vec<f32>,
+    crack: Vec<bool>,
+    coverage: Vec<f32>,
+}
+
+impl IceSurface {
+    fn generate(
+        sprite_width: u32,
+        sprite_height: u32,
+        seed: u64,
+        config: &TileConfig,
+        transition_mask: u8,
+        overrides: Option<&TransitionOverrides>,
+    ) -> Result<Self, String> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let crack_count = require_field(config.ice_crack_count, "ice_crack_count")?;
+        let streak_count = require_field(config.ice_streak_count, "ice_streak_count")?;
+        let (cutoff, gradient) = if transition_mask != 0 {
+            transition_edge_settings(
+                "ice",
+                (config.ice_edge_cutoff, config.ice_edge_gradient),
+                overrides.map(|overrides| (overrides.ice_edge_cutoff, overrides.ice_edge_gradient)),
+            )?
+        } else {
+            (0.0, 0.0)
+        };
+
+        let mut base = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+        draw_isometric_ground(
+            &mut base,
+            sprite_width,
+            sprite_height,
+            Rgba([255, 255, 255, 255]),
+        );
+        let len = (sprite_width * sprite_height) as usize;
+        let mut streak = vec![0.0; len];
+        let mut crack = vec![false; len];
+        let mut coverage = vec![0.0; len];
+
+        // Specular streaks run diagonally across the tile like reflected sky.
+        let streaks: Vec<(f32, f32)> = (0..streak_count)
+            .map(|_| (rng.gen_range(0.0..1.0), rng.gen_range(0.03..0.1)))
+            .collect();
+        let width = sprite_width.max(1) as f32;
+        for (x, y, pixel) in base.enumerate_pixels() {
+            if pixel.0[3] == 0 {
+                continue;
+            }
+            let idx = (y * sprite_width + x) as usize;
+            let xf = x as f32 / width;
+            let yf = y as f32 / width;
+            let (u, v) = uv_from_xy(xf, yf);
+            let along = u + 0.1 * v;
+            let mut glint: f32 = 0.0;
+            for (center, half_width) in &streaks {
+                let distance = (along - center).abs();
+                glint = glint.max(1.0 - smoothstep(0.0, *half_width, distance));
+            }
+            streak[idx] = glint;
+            coverage[idx] = if transition_mask != 0 {
+                edge_weight_for_mask(transition_mask, xf, yf, cutoff, gradient)
+            } else {
+                1.0
+            };
+        }
+
+        let crack_steps = (sprite_width / 3).max(8);
+        for _ in 0..crack_count {
+            let (start_x, start_y) = random_tile_point(&base, &mut rng);
+            let mut angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let mut branches = vec![(start_x as f32, start_y as f32, angle, crack_steps)];
+            let mut branch_budget = 2;
+            while let Some((mut px, mut py, heading, steps)) = branches.pop() {
+                angle = heading;
+                for _ in 0..steps {
+                    angle += rng.gen_range(-0.25..0.25);
+                    // Half-pixel steps keep the crack line connected.
+                    px += angle.cos() * 0.5;
+                    py += angle.sin() * 0.25;
+                    let (ix, iy) = (px.round() as i32, py.round() as i32);
+                    if ix < 0 || iy < 0 || ix >= sprite_width as i32 || iy >= sprite_height as i32
+                    {
+                        break;
+                    }
+                    if base.get_pixel(ix as u32, iy as u32).0[3] == 0 {
+                        break;
+                    }
+                    crack[(iy as u32 * sprite_width + ix as u32) as usize] = true;
+                    if branch_budget > 0 && rng.gen_bool(0.01) {
+                        branch_budget -= 1;
+                        branches.push((px, py, angle + rng.gen_range(-1.2..1.2), steps / 2));
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            base,
+            streak,
+            crack,
+            coverage,
+        })
+    }
+}
+
+fn check_ice_name(config: &TileConfig, transition_mask: u8) -> Result<(), String> {
+    let expected = if transition_mask != 0 {
+        "ice_transition"
+    } else {
+        "ice"
+    };
+    if config.name != expected {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    Ok(())
+}
+
+fn ice_palette(config: &TileConfig) -> Result<[Rgba<u8>; 3], String> {
+    let base_hex = require_field(config.ice_base.clone(), "ice_base")?;
+    let crack_hex = require_field(config.ice_crack.clone(), "ice_crack")?;
+    let highlight_hex = require_field(config.ice_highlight.clone(), "ice_highlight")?;
+    Ok([
+        parse_hex_color(&base_hex)?,
+        parse_hex_color(&crack_hex)?,
+        parse_hex_color(&highlight_hex)?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spriteforge_assets::EDGE_N;
+
+    #[test]
+    fn mask_clears_cracks_and_keeps_glint_on_streaks() {
+        let config = TileConfig {
+            name: "ice".to_string(),
+            ice_crack_count: Some(4),
+            ice_streak_count: Some(2),
+            ..Default::default()
+        };
+        let (width, height, seed) = (64, 64, 7);
+        let surface = IceSurface::generate(width, height, seed, &config, 0, None).unwrap();
+        let mask = render_ice_mask_tile(width, height, seed, &config, 0, None).unwrap();
+        let (mut cracks, mut streaks) = (0, 0);
+        for (x, y, pixel) in mask.enumerate_pixels() {
+            let idx = (y * width + x) as usize;
+            if surface.base.get_pixel(x, y).0[3] == 0 {
+                continue;
+            }
+            if surface.crack[idx] {
+                cracks += 1;
+                assert_eq!(pixel.0[3], 0, "crack at ({x}, {y})");
+            } else if surface.streak[idx] > 0.5 {
+                streaks += 1;
+                assert!(pixel.0[3] as f32 > MASK_BASE_ALPHA, "streak at ({x}, {y})");
+            }
+        }
+        assert!(cracks > 0 && streaks > 0);
+    }
+
+    #[test]
+    fn entry_overrides_replace_the_config_edge_settings() {
+        let config = TileConfig {
+            name: "ice_transition".to_string(),
+            ice_crack_count: Some(0),
+            ice_streak_count: Some(0),
+            ice_edge_cutoff: Some(0.1),
+            ice_edge_gradient: Some(0.2),
+            ..Default::default()
+        };
+        let overrides = TransitionOverrides {
+            ice_edge_cutoff: Some(0.6),
+            ..Default::default()
+        };
+        let covered = |overrides| {
+            let surface = IceSurface::generate(64, 64, 3, &config, EDGE_N, overrides).unwrap();
+            surface.coverage.iter().filter(|weight| **weight > 0.0).count()
+        };
+        assert!(covered(Some(&overrides)) < covered(None));
+    }
+}
@@ -2,22 +2,43 @@ use image::{ImageBuffer, Rgba};
 
 use crate::config::{TileConfig, TilesheetEntry, TransitionOverrides};
 
+mod block;
+mod bridge;
+mod building;
+mod crop;
 mod debug_weight;
+mod decoration;
 mod dirt;
+mod farmland;
+mod fence;
 mod grass;
+mod ice;
 mod path;
+mod pier;
+mod ramp;
+mod rock;
+mod sand;
+mod snow;
 mod tree;
 pub mod transition;
 mod util;
 mod water;
 
+pub use building::building_layout;
+pub use crop::CROP_KINDS;
+pub use decoration::{decoration_layout, DECORATION_KINDS};
+pub use farmland::FARMLAND_MOISTURE;
+pub use rock::ROCK_SIZE_CLASSES;
+pub use tree::{plant_model, tree_colors_from_config, TreeModelCache};
 pub use util::parse_hex_color;
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_tilesheet(
     sprite_width: u32,
     sprite_height: u32,
     bg: Rgba<u8>,
     config: &TileConfig,
+    trees: &TreeModelCache,
     entries: &[TilesheetEntry],
     columns: u32,
     padding: u32,
@@ -51,11 +72,12 @@ pub fn render_tilesheet(
             entries,
             columns,
             padding,
-            |mask, _seed, overrides| {
+            |mask, seed, overrides| {
                 water::render_water_transition_tile(
                     sprite_width,
                     sprite_height,
                     bg,
+                    seed,
                     config,
                     mask,
                     overrides,
@@ -83,15 +105,7 @@ pub fn render_tilesheet(
     let mut sheet = ImageBuffer::from_pixel(sheet_w, sheet_h, Rgba([0, 0, 0, 0]));
 
     for (i, entry) in entries.iter().enumerate() {
-        let tile = render_tile(
-            sprite_width,
-            sprite_height,
-            bg,
-            entry.seed,
-            config,
-            entry.transition_mask,
-            Some(&entry.overrides),
-        )?;
+        let tile = render_entry(sprite_width, sprite_height, bg, config, trees, entry)?;
         let col = (i as u32) % cols;
         let row = (i as u32) / cols;
         let x = (col * sprite_width + padding * col) as i32;
@@ -102,10 +116,162 @@ pub fn render_tilesheet(
     Ok(sheet)
 }
 
+fn render_entry(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    config: &TileConfig,
+    trees: &TreeModelCache,
+    entry: &TilesheetEntry,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if let Some(cliff_mask) = entry.cliff_mask {
+        return block::render_block_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            entry.seed,
+            config,
+            cliff_mask,
+        );
+    }
+    if let Some(rise_direction) = entry.rise_direction {
+        return ramp::render_ramp_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            entry.seed,
+            config,
+            rise_direction,
+        );
+    }
+    if let Some(size_class) = entry.size_class.as_deref() {
+        return rock::render_rock_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            entry.seed,
+            config,
+            size_class,
+        );
+    }
+    if let Some(moisture) = entry.moisture.as_deref() {
+        return farmland::render_farmland_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            entry.seed,
+            config,
+            entry.connection_mask.unwrap_or(transition::EDGE_N | transition::EDGE_S),
+            moisture,
+        );
+    }
+    if let (Some(kind), Some(stage)) = (entry.crop.as_deref(), entry.stage) {
+        return crop::render_crop_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            entry.seed,
+            config,
+            kind,
+            stage,
+        );
+    }
+    if let Some(season) = entry.season.as_deref() {
+        let variant = tree::TreeVariant {
+            season,
+            stage: entry.stage,
+            frame: entry.frame.unwrap_or(0),
+            rotation: entry.rotation.unwrap_or(0),
+        };
+        return tree::render_tree_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            entry.seed,
+            config,
+            trees,
+            variant,
+        );
+    }
+    if let Some(roof) = entry.roof.as_deref() {
+        return building::render_building_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            entry.seed,
+            config,
+            building::BuildingStyle {
+                roof,
+                material: entry.material.as_deref().unwrap_or(building::WALL_MATERIALS[0]),
+            },
+        );
+    }
+    if let Some(connection_mask) = entry.connection_mask {
+        return match config.name.as_str() {
+            "bridge" => bridge::render_bridge_tile(
+                sprite_width,
+                sprite_height,
+                bg,
+                entry.seed,
+                config,
+                bridge::BridgePiece {
+                    connection_mask,
+                    landing_mask: entry.landing_mask.unwrap_or(0),
+                    material: entry.material.as_deref().unwrap_or(bridge::BRIDGE_MATERIALS[0]),
+                },
+            ),
+            "wall" => fence::render_wall_tile(
+                sprite_width,
+                sprite_height,
+                bg,
+                entry.seed,
+                config,
+                connection_mask,
+            ),
+            "pier" => pier::render_pier_tile(
+                sprite_width,
+                sprite_height,
+                bg,
+                entry.seed,
+                config,
+                connection_mask,
+            ),
+            _ => fence::render_fence_tile(
+                sprite_width,
+                sprite_height,
+                bg,
+                entry.seed,
+                config,
+                connection_mask,
+            ),
+        };
+    }
+    if let Some(kind) = entry.decoration.as_deref() {
+        return decoration::render_decoration_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            entry.seed,
+            config,
+            kind,
+        );
+    }
+    render_tile(
+        sprite_width,
+        sprite_height,
+        bg,
+        entry.seed,
+        config,
+        entry.transition_mask,
+        Some(&entry.overrides),
+    )
+}
+
 pub fn render_tilesheet_mask(
     sprite_width: u32,
     sprite_height: u32,
     config: &TileConfig,
+    trees: &TreeModelCache,
     entries: &[TilesheetEntry],
     columns: u32,
     padding: u32,
@@ -135,19 +301,136 @@ pub fn render_tilesheet_mask(
     let mut sheet = ImageBuffer::from_pixel(sheet_w, sheet_h, Rgba([0, 0, 0, 0]));
 
     for (i, entry) in entries.iter().enumerate() {
-        let mask_tile = render_tile_mask(
+        let mask_tile = if let Some(season) = entry.season.as_deref() {
+            let variant = tree::TreeVariant {
+                season,
+                stage: entry.stage,
+                frame: entry.frame.unwrap_or(0),
+                rotation: entry.rotation.unwrap_or(0),
+            };
+            tree::render_tree_mask_tile(
+                sprite_width,
+                sprite_height,
+                entry.seed,
+                config,
+                trees,
+                variant,
+            )?
+        } else {
+            match (entry.size_class.as_deref(), entry.roof.as_deref()) {
+                (Some(size_class), _) => rock::render_rock_mask_tile(
+                    sprite_width,
+                    sprite_height,
+                    entry.seed,
+                    config,
+                    size_class,
+                )?,
+                (None, Some(roof)) => {
+                    building::render_building_mask_tile(sprite_width, sprite_height, config, roof)?
+                }
+                (None, None) => render_tile_mask(
+                    sprite_width,
+                    sprite_height,
+                    entry.seed,
+                    config,
+                    entry.transition_mask,
+                    Some(&entry.overrides),
+                )?,
+            }
+        };
+        let col = (i as u32) % cols;
+        let row = (i as u32) / cols;
+        let x = (col * sprite_width + padding * col) as i32;
+        let y = (row * sprite_height + padding * row) as i32;
+        util::blit_offset(&mut sheet, &mask_tile, x, y);
+    }
+
+    Ok(sheet)
+}
+
+/// Drop shadows for a tree or bush sheet, laid out tile for tile like the sheet itself.
+pub fn render_tilesheet_shadow(
+    sprite_width: u32,
+    sprite_height: u32,
+    config: &TileConfig,
+    trees: &TreeModelCache,
+    entries: &[TilesheetEntry],
+    columns: u32,
+    padding: u32,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    let cols = columns.max(1);
+    let rows = (entries.len() as u32).div_ceil(cols);
+    let sheet_w = cols * sprite_width + padding * (cols.saturating_sub(1));
+    let sheet_h = rows * sprite_height + padding * (rows.saturating_sub(1));
+    let mut sheet = ImageBuffer::from_pixel(sheet_w, sheet_h, Rgba([0, 0, 0, 0]));
+
+    for (i, entry) in entries.iter().enumerate() {
+        let Some(season) = entry.season.as_deref() else {
+            return Err(format!("No shadow renderer for tile name: {}", config.name));
+        };
+        let variant = tree::TreeVariant {
+            season,
+            stage: entry.stage,
+            frame: entry.frame.unwrap_or(0),
+            rotation: entry.rotation.unwrap_or(0),
+        };
+        let shadow_tile = tree::render_tree_shadow_tile(
             sprite_width,
             sprite_height,
             entry.seed,
             config,
-            entry.transition_mask,
-            Some(&entry.overrides),
+            trees,
+            variant,
         )?;
         let col = (i as u32) % cols;
         let row = (i as u32) / cols;
         let x = (col * sprite_width + padding * col) as i32;
         let y = (row * sprite_height + padding * row) as i32;
-        util::blit_offset(&mut sheet, &mask_tile, x, y);
+        util::blit_offset(&mut sheet, &shadow_tile, x, y);
+    }
+
+    Ok(sheet)
+}
+
+/// Lays out the 16-bit height and depth tiles of a tree or bush sheet in the sheet's layout.
+pub fn render_tilesheet_depth(
+    sprite_width: u32,
+    sprite_height: u32,
+    config: &TileConfig,
+    trees: &TreeModelCache,
+    entries: &[TilesheetEntry],
+    columns: u32,
+    padding: u32,
+) -> Result<ImageBuffer<Rgba<u16>, Vec<u16>>, String> {
+    let cols = columns.max(1);
+    let rows = (entries.len() as u32).div_ceil(cols);
+    let sheet_w = cols * sprite_width + padding * (cols.saturating_sub(1));
+    let sheet_h = rows * sprite_height + padding * (rows.saturating_sub(1));
+    let mut sheet = ImageBuffer::from_pixel(sheet_w, sheet_h, Rgba([0, 0, 0, 0]));
+
+    for (i, entry) in entries.iter().enumerate() {
+        let Some(season) = entry.season.as_deref() else {
+            return Err(format!("No depth renderer for tile name: {}", config.name));
+        };
+        let variant = tree::TreeVariant {
+            season,
+            stage: entry.stage,
+            frame: entry.frame.unwrap_or(0),
+            rotation: entry.rotation.unwrap_or(0),
+        };
+        let depth_tile = tree::render_tree_depth_tile(
+            sprite_width,
+            sprite_height,
+            entry.seed,
+            config,
+            trees,
+            variant,
+        )?;
+        let col = (i as u32) % cols;
+        let row = (i as u32) / cols;
+        let x = col * sprite_width + padding * col;
+        let y = row * sprite_height + padding * row;
+        image::imageops::replace(&mut sheet, &depth_tile, x as i64, y as i64);
     }
 
     Ok(sheet)
@@ -170,7 +453,27 @@ fn render_tile_mask(
             transition_mask.unwrap_or(transition::EDGE_N),
             overrides,
         ),
-        "tree" | "bush" => tree::render_tree_mask_tile(sprite_width, sprite_height, seed, config),
+        "ice" => ice::render_ice_mask_tile(sprite_width, sprite_height, seed, config, 0, None),
+        "ice_transition" => ice::render_ice_mask_tile(
+            sprite_width,
+            sprite_height,
+            seed,
+            config,
+            transition_mask.unwrap_or(transition::EDGE_N),
+            overrides,
+        ),
+        "rock" => rock::render_rock_mask_tile(sprite_width, sprite_height, seed, config, "rock"),
+        "tree" | "bush" => {
+            let variant = tree::TreeVariant::default();
+            let trees = TreeModelCache::default();
+            tree::render_tree_mask_tile(sprite_width, sprite_height, seed, config, &trees, variant)
+        }
+        "building" => building::render_building_mask_tile(
+            sprite_width,
+            sprite_height,
+            config,
+            building::ROOF_STYLES[0],
+        ),
         other => Err(format!("No mask renderer for tile name: {other}")),
     }
 }
@@ -196,17 +499,48 @@ pub fn render_tile(
             transition_mask.unwrap_or(transition::EDGE_N),
             overrides,
         ),
-        "water" => water::render_water_tile(sprite_width, sprite_height, bg, config),
+        "water" => water::render_water_tile(sprite_width, sprite_height, bg, seed, config),
         "water_transition" => {
             water::render_water_transition_tile(
                 sprite_width,
                 sprite_height,
                 bg,
+                seed,
                 config,
                 transition_mask.unwrap_or(transition::EDGE_N),
                 overrides,
             )
         }
+        "sand" => sand::render_sand_tile(sprite_width, sprite_height, bg, seed, config),
+        "sand_transition" => sand::render_sand_transition_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition_mask.unwrap_or(transition::EDGE_N),
+            overrides,
+        ),
+        "snow" => snow::render_snow_tile(sprite_width, sprite_height, bg, seed, config),
+        "snow_transition" => snow::render_snow_transition_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition_mask.unwrap_or(transition::EDGE_N),
+            overrides,
+        ),
+        "ice" => ice::render_ice_tile(sprite_width, sprite_height, bg, seed, config, 0, None),
+        "ice_transition" => ice::render_ice_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition_mask.unwrap_or(transition::EDGE_N),
+            overrides,
+        ),
         "path" => path::render_path_tile(sprite_width, sprite_height, bg, config),
         "path_transition" => path::render_path_transition_tile(
             sprite_width,
@@ -215,7 +549,102 @@ pub fn render_tile(
             config,
             transition_mask.unwrap_or(transition::EDGE_N),
         ),
-        "tree" | "bush" => tree::render_tree_tile(sprite_width, sprite_height, bg, seed, config),
+        // A standalone block is drawn as if every neighbor were lower.
+        "block" => block::render_block_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition::EDGE_MASK,
+        ),
+        "ramp" => ramp::render_ramp_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition::EDGE_N,
+        ),
+        "rock" => rock::render_rock_tile(sprite_width, sprite_height, bg, seed, config, "rock"),
+        "fence" => fence::render_fence_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition::EDGE_MASK,
+        ),
+        "bridge" => bridge::render_bridge_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            bridge::BridgePiece {
+                connection_mask: transition::EDGE_N | transition::EDGE_S,
+                landing_mask: 0,
+                material: bridge::BRIDGE_MATERIALS[0],
+            },
+        ),
+        "wall" => fence::render_wall_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition::EDGE_MASK,
+        ),
+        "pier" => pier::render_pier_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition::EDGE_N | transition::EDGE_S,
+        ),
+        "farmland" => farmland::render_farmland_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            transition::EDGE_N | transition::EDGE_S,
+            FARMLAND_MOISTURE[0],
+        ),
+        "crop" => crop::render_crop_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            CROP_KINDS[0],
+            crop::MIN_CROP_STAGES - 1,
+        ),
+        "building" => building::render_building_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            building::BuildingStyle {
+                roof: building::ROOF_STYLES[0],
+                material: building::WALL_MATERIALS[0],
+            },
+        ),
+        "decoration" => decoration::render_decoration_tile(
+            sprite_width,
+            sprite_height,
+            bg,
+            seed,
+            config,
+            "flower",
+        ),
+        "tree" | "bush" => {
+            let variant = tree::TreeVariant::default();
+            let trees = TreeModelCache::default();
+            tree::render_tree_tile(sprite_width, sprite_height, bg, seed, config, &trees, variant)
+        }
         "debug_weight" => debug_weight::render_weight_debug_tile(
             sprite_width,
             sprite_height,
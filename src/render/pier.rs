@@ -0,0 +1,134 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::fence::{connected_edges, draw_boxes, stone_variation, Face, IsoBox, Projector};
+use crate::render::util::{mix_color, parse_hex_color};
+use spriteforge_assets::{EDGE_E, EDGE_N, EDGE_S, EDGE_W};
+
+const POST: u8 = 0;
+const DECK: u8 = 1;
+const POST_HALF_SIZE: f32 = 0.035;
+const SUBMERGED_TINT: f32 = 0.55;
+
+type Sprite = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+/// Renders a plank pier standing in water. The deck runs from the tile center to every edge
+/// in `connection_mask`; posts reach down to the water and rise above the deck at open ends.
+pub fn render_pier_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    connection_mask: u8,
+) -> Result<Sprite, String> {
+    if config.name != "pier" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let deck_height = require_field(config.pier_deck_height, "pier_deck_height")? as f32;
+    let half_width = require_field(config.pier_width, "pier_width")?.clamp(0.1, 1.0) * 0.5;
+    let waterline = require_field(config.pier_waterline, "pier_waterline")? as f32;
+    let hexes = require_field(config.pier_colors.clone(), "pier_colors")?;
+    let [light, base, dark] = [
+        parse_hex_color(&hexes[0])?,
+        parse_hex_color(&hexes[1])?,
+        parse_hex_color(&hexes[2])?,
+    ];
+    let water = parse_hex_color(&require_field(
+        config.pier_water_color.clone(),
+        "pier_water_color",
+    )?)?;
+
+    let thickness = (deck_height * 0.3).max(3.0);
+    let deck_bottom = deck_height - thickness;
+    let (low, high) = (0.5 - half_width, 0.5 + half_width);
+    let mut boxes = vec![IsoBox::new(
+        [low, low, deck_bottom],
+        [high, high, deck_height],
+        DECK,
+    )];
+    let edges = connected_edges(connection_mask);
+    for &edge in &edges {
+        boxes.push(IsoBox {
+            part: DECK,
+            ..IsoBox::arm(edge, half_width, deck_bottom, deck_height)
+        });
+    }
+
+    // Corner posts of the center pad; the ones at open ends double as bollards.
+    let post_inset = half_width - POST_HALF_SIZE;
+    for (du, dv) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+        let u_edge = if du < 0.0 { EDGE_W } else { EDGE_E };
+        let v_edge = if dv < 0.0 { EDGE_S } else { EDGE_N };
+        let open = connection_mask & (u_edge | v_edge) == 0;
+        let top = if open {
+            deck_height + thickness * 1.5
+        } else {
+            deck_bottom
+        };
+        boxes.push(post_at(0.5 + du * post_inset, 0.5 + dv * post_inset, top));
+    }
+    // Posts halfway along each arm keep the spacing even across neighboring tiles.
+    for &edge in &edges {
+        let (along_u, offset) = match edge {
+            EDGE_N => (false, 0.25),
+            EDGE_S => (false, -0.25),
+            EDGE_E => (true, 0.25),
+            _ => (true, -0.25),
+        };
+        for side in [-post_inset, post_inset] {
+            let (u, v) = if along_u {
+                (0.5 + offset, 0.5 + side)
+            } else {
+                (0.5 + side, 0.5 + offset)
+            };
+            boxes.push(post_at(u, v, deck_bottom));
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    let projector = Projector::new(sprite_width, sprite_height);
+    let plank = (projector.width * 0.5 / 14.0).max(3.0);
+    draw_boxes(
+        &mut img,
+        &projector,
+        &mut boxes,
+        |iso_box, face, along, z| {
+            let speckle = rng.gen_range(0..10);
+            if iso_box.part == POST {
+                let wood = if speckle == 0 { base } else { dark };
+                // Wet wood below the waterline takes on the water color.
+                return if z < waterline {
+                    mix_color(wood, water, SUBMERGED_TINT)
+                } else {
+                    wood
+                };
+            }
+            if face == Face::Top {
+                let position = along / plank;
+                if position.fract() < 0.15 {
+                    return dark;
+                }
+                let grain = stone_variation(position.floor(), 0.0);
+                return mix_color(base, light, grain * 0.7);
+            }
+            match speckle {
+                0 => dark,
+                1 => light,
+                _ => base,
+            }
+        },
+    );
+    Ok(img)
+}
+
+fn post_at(u: f32, v: f32, top: f32) -> IsoBox {
+    IsoBox::new(
+        [u - POST_HALF_SIZE, v - POST_HALF_SIZE, 0.0],
+        [u + POST_HALF_SIZE, v + POST_HALF_SIZE, top],
+        POST,
+    )
+}
@@ -0,0 +1,192 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::config::{require_field, TileConfig};
+use crate::render::block::{shade_color, BlockFaces};
+use spriteforge_assets::{
+    uv_from_xy, CORNER_NE, CORNER_NW, CORNER_SE, CORNER_SW, EDGE_E, EDGE_N, EDGE_S, EDGE_W,
+};
+
+/// Renders a ramp that climbs `block_height` pixels toward `rise_direction`, which is a
+/// single edge bit (straight ramp), a corner bit (outer corner ramp) or two adjacent edge
+/// bits (inner corner ramp, high along both edges). The surface is the
+/// flat `ramp_surface` tile warped in uv space, so its texture lines up with flat neighbors.
+pub fn render_ramp_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    rise_direction: u8,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if config.name != "ramp" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let surface_name = require_field(config.ramp_surface.clone(), "ramp_surface")?;
+    if surface_name == "ramp" || surface_name == "block" {
+        return Err(format!("ramp_surface cannot be {surface_name}"));
+    }
+    // Validate before drawing so unknown directions do not silently render flat tiles.
+    ramp_height(rise_direction, 0.0, 0.0)?;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let faces = BlockFaces::from_config(&mut rng, config, sprite_width, sprite_height)?;
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    faces.draw_side_faces(&mut img, &mut rng, |edge, t| {
+        let (u, v) = if edge == EDGE_S { (t, 0.0) } else { (1.0, t) };
+        ramp_height(rise_direction, u, v).unwrap_or(0.0)
+    });
+
+    let mut surface_config = config.clone();
+    surface_config.name = surface_name;
+    let flat = super::render_tile(
+        sprite_width,
+        sprite_height,
+        Rgba([0, 0, 0, 0]),
+        seed,
+        &surface_config,
+        None,
+        None,
+    )?;
+
+    let lift = faces.height as f32;
+    let width = sprite_width.max(1) as f32;
+    let slope_scale = lift / (width * 0.5);
+    for x in 0..sprite_width {
+        let mut previous_row: Option<i32> = None;
+        for y in 0..sprite_height {
+            let pixel = *flat.get_pixel(x, y);
+            if pixel.0[3] == 0 {
+                previous_row = None;
+                continue;
+            }
+            let (u, v) = uv_from_xy(x as f32 / width, y as f32 / width);
+            let (u, v) = (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+            let height = ramp_height(rise_direction, u, v)?;
+            let (du, dv) = ramp_gradient(rise_direction, u, v);
+            let color = shade_color(pixel, slope_light(du * slope_scale, dv * slope_scale));
+
+            // Slopes facing away from the viewer stretch on screen, so fill the gap back to
+            // the previously written row in this column.
+            let row = (y as f32 - height * lift).round() as i32;
+            let from = match previous_row {
+                Some(previous) if row > previous + 1 => previous + 1,
+                _ => row,
+            };
+            for out_y in from..=row {
+                if out_y >= 0 && (out_y as u32) < sprite_height {
+                    img.put_pixel(x, out_y as u32, color);
+                }
+            }
+            previous_row = Some(row);
+        }
+    }
+    Ok(img)
+}
+
+const INNER_NE: u8 = EDGE_N | EDGE_E;
+const INNER_SE: u8 = EDGE_S | EDGE_E;
+const INNER_SW: u8 = EDGE_S | EDGE_W;
+const INNER_NW: u8 = EDGE_N | EDGE_W;
+
+fn ramp_height(rise_direction: u8, u: f32, v: f32) -> Result<f32, String> {
+    Ok(match rise_direction {
+        EDGE_N => v,
+        EDGE_E => u,
+        EDGE_S => 1.0 - v,
+        EDGE_W => 1.0 - u,
+        CORNER_NE => u.min(v),
+        CORNER_SE => u.min(1.0 - v),
+        CORNER_SW => (1.0 - u).min(1.0 - v),
+        CORNER_NW => (1.0 - u).min(v),
+        INNER_NE => u.max(v),
+        INNER_SE => u.max(1.0 - v),
+        INNER_SW => (1.0 - u).max(1.0 - v),
+        INNER_NW => (1.0 - u).max(v),
+        other => return Err(format!("Unknown ramp rise direction: {other}")),
+    })
+}
+
+fn ramp_gradient(rise_direction: u8, u: f32, v: f32) -> (f32, f32) {
+    let corner = |du: f32, dv: f32, u_wins: bool| if u_wins { (du, 0.0) } else { (0.0, dv) };
+    match rise_direction {
+        EDGE_N => (0.0, 1.0),
+        EDGE_E => (1.0, 0.0),
+        EDGE_S => (0.0, -1.0),
+        EDGE_W => (-1.0, 0.0),
+        CORNER_NE => corner(1.0, 1.0, u < v),
+        CORNER_SE => corner(1.0, -1.0, u < 1.0 - v),
+        CORNER_SW => corner(-1.0, -1.0, 1.0 - u < 1.0 - v),
+        CORNER_NW => corner(-1.0, 1.0, 1.0 - u < v),
+        INNER_NE => corner(1.0, 1.0, u > v),
+        INNER_SE => corner(1.0, -1.0, u > 1.0 - v),
+        INNER_SW => corner(-1.0, -1.0, 1.0 - u > 1.0 - v),
+        INNER_NW => corner(-1.0, 1.0, 1.0 - u > v),
+        _ => (0.0, 0.0),
+    }
+}
+
+/// Brightness relative to flat ground for a light from the upper-left of the screen.
+fn slope_light(du: f32, dv: f32) -> f32 {
+    let light = [-0.6_f32, 0.2, 0.77];
+    let normal = [-du, -dv, 1.0];
+    let length = (normal[0] * normal[0] + normal[1] * normal[1] + 1.0).sqrt();
+    let lit = (normal[0] * light[0] + normal[1] * light[1] + normal[2] * light[2]) / length;
+    (lit / light[2]).clamp(0.6, 1.3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spriteforge_assets::all_ramp_directions;
+
+    #[test]
+    fn every_ramp_climbs_from_zero_on_its_low_side_to_full_lift_on_its_high_side() {
+        let edges = [
+            (EDGE_N, CORNER_NE, CORNER_NW),
+            (EDGE_E, CORNER_NE, CORNER_SE),
+            (EDGE_S, CORNER_SE, CORNER_SW),
+            (EDGE_W, CORNER_SW, CORNER_NW),
+        ];
+        let edge_point = |edge: u8, t: f32| match edge {
+            EDGE_N => (t, 1.0),
+            EDGE_E => (1.0, t),
+            EDGE_S => (t, 0.0),
+            _ => (0.0, t),
+        };
+        let corner_point = |corner: u8| match corner {
+            CORNER_NE => (1.0, 1.0),
+            CORNER_SE => (1.0, 0.0),
+            CORNER_SW => (0.0, 0.0),
+            _ => (0.0, 1.0),
+        };
+        for direction in all_ramp_directions() {
+            // A tile corner is high when it is the rise corner or touches a rising edge.
+            let high = |corner: u8| {
+                direction == corner
+                    || edges
+                        .iter()
+                        .any(|(edge, a, b)| direction & edge != 0 && (*a == corner || *b == corner))
+            };
+            for corner in [CORNER_NE, CORNER_SE, CORNER_SW, CORNER_NW] {
+                let (u, v) = corner_point(corner);
+                let expected = if high(corner) { 1.0 } else { 0.0 };
+                let height = ramp_height(direction, u, v).unwrap();
+                assert_eq!(height, expected, "{direction}");
+            }
+            for (edge, a, b) in edges {
+                let expected = match (high(a), high(b)) {
+                    (true, true) => 1.0,
+                    (false, false) => 0.0,
+                    _ => continue,
+                };
+                for step in 0..=4 {
+                    let (u, v) = edge_point(edge, step as f32 / 4.0);
+                    let height = ramp_height(direction, u, v).unwrap();
+                    assert_eq!(height, expected, "{direction}");
+                }
+            }
+        }
+    }
+}
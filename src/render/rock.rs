@@ -0,0 +1,215 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig};
+use crate::render::tree::{encode_normal, project_raw};
+use crate::render::util::{mix_color, parse_hex_color};
+use crate::rock::{generate_rock, RockSettings};
+use crate::tree::Vec3;
+
+pub const ROCK_SIZE_CLASSES: [&str; 3] = ["pebble", "rock", "boulder"];
+
+pub fn render_rock_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    size_class: &str,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    let raster = RockRaster::generate(sprite_width, sprite_height, seed, config, size_class)?;
+    let colors = require_field(config.rock_colors.clone(), "rock_colors")?;
+    let base = parse_hex_color(&colors[0])?;
+    let shadow = parse_hex_color(&colors[1])?;
+    let highlight = parse_hex_color(&colors[2])?;
+
+    // Each facet gets one flat shade so the rock reads as chiseled.
+    let light = Vec3::new(-0.5, 0.3, 0.8).normalized();
+    let mut rng = StdRng::seed_from_u64(seed ^ 0x5eed);
+    let facet_colors: Vec<Rgba<u8>> = raster
+        .normals
+        .iter()
+        .map(|normal| {
+            let lit = (dot(*normal, light) + rng.gen_range(-0.06..0.06)).clamp(0.0, 1.0);
+            if lit < 0.5 {
+                mix_color(shadow, base, lit / 0.5)
+            } else {
+                mix_color(base, highlight, (lit - 0.5) / 0.5)
+            }
+        })
+        .collect();
+
+    let mut tile = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    for (idx, facet) in raster.facets.iter().enumerate() {
+        if let Some(facet) = facet {
+            let x = idx as u32 % sprite_width;
+            let y = idx as u32 / sprite_width;
+            tile.put_pixel(x, y, facet_colors[*facet]);
+        }
+    }
+    Ok(tile)
+}
+
+pub fn render_rock_mask_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    seed: u64,
+    config: &TileConfig,
+    size_class: &str,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    let raster = RockRaster::generate(sprite_width, sprite_height, seed, config, size_class)?;
+    let mut mask = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+    for (idx, facet) in raster.facets.iter().enumerate() {
+        if let Some(facet) = facet {
+            let x = idx as u32 % sprite_width;
+            let y = idx as u32 / sprite_width;
+            mask.put_pixel(x, y, encode_normal(raster.normals[*facet]));
+        }
+    }
+    Ok(mask)
+}
+
+pub fn rock_settings_from_config(
+    config: &TileConfig,
+    size_class: &str,
+) -> Result<RockSettings, String> {
+    let sizes = require_field(config.rock_sizes, "rock_sizes")?;
+    let class_index = ROCK_SIZE_CLASSES
+        .iter()
+        .position(|name| *name == size_class)
+        .ok_or_else(|| format!("Unknown rock size class: {size_class}"))?;
+    Ok(RockSettings {
+        radius: sizes[class_index],
+        squash: require_field(config.rock_squash, "rock_squash")?,
+        roughness: require_field(config.rock_roughness, "rock_roughness")?,
+        rings: require_field(config.rock_rings, "rock_rings")?,
+        segments: require_field(config.rock_segments, "rock_segments")?,
+    })
+}
+
+/// Per-pixel visible facet of a rock model, shared by the color and normal mask renderers.
+struct RockRaster {
+    facets: Vec<Option<usize>>,
+    normals: Vec<Vec3>,
+}
+
+impl RockRaster {
+    fn generate(
+        sprite_width: u32,
+        sprite_height: u32,
+        seed: u64,
+        config: &TileConfig,
+        size_class: &str,
+    ) -> Result<Self, String> {
+        if config.name != "rock" {
+            return Err(format!("Unknown tile name: {}", config.name));
+        }
+        let settings = rock_settings_from_config(config, size_class)?;
+        let model = generate_rock(seed, &settings);
+        let normals: Vec<Vec3> = model
+            .triangles
+            .iter()
+            .map(|triangle| model.face_normal(*triangle))
+            .collect();
+
+        // Every size class shares one scale so pebbles stay small next to boulders.
+        let sizes = require_field(config.rock_sizes, "rock_sizes")?;
+        let largest = sizes.iter().cloned().fold(0.0_f32, f32::max).max(0.001);
+        let extent = largest * (1.0 + settings.roughness) * 1.2 * std::f32::consts::SQRT_2;
+        let scale = sprite_width as f32 * 0.45 / extent;
+        let origin_x = sprite_width.saturating_sub(1) as f32 * 0.5;
+        let origin_y = sprite_height.saturating_sub(1) as f32 - sprite_width as f32 * 0.25;
+        let project = |point: Vec3| {
+            let (raw_x, raw_y) = project_raw(point);
+            (raw_x * scale + origin_x, raw_y * scale + origin_y)
+        };
+
+        let len = (sprite_width * sprite_height) as usize;
+        let mut facets = vec![None; len];
+        let mut depth = vec![f32::NEG_INFINITY; len];
+        let view = Vec3::new(1.0, 1.0, 1.0);
+        for (facet, triangle) in model.triangles.iter().enumerate() {
+            if dot(normals[facet], view) <= 0.0 {
+                continue;
+            }
+            rasterize_triangle(
+                triangle.map(|index| model.vertices[index]),
+                &project,
+                sprite_width,
+                sprite_height,
+                |idx, depth_value| {
+                    if depth_value > depth[idx] {
+                        depth[idx] = depth_value;
+                        facets[idx] = Some(facet);
+                    }
+                },
+            );
+        }
+        Ok(Self { facets, normals })
+    }
+}
+
+pub(super) fn rasterize_triangle<P, F>(
+    points: [Vec3; 3],
+    project: &P,
+    sprite_width: u32,
+    sprite_height: u32,
+    mut plot: F,
+) where
+    P: Fn(Vec3) -> (f32, f32),
+    F: FnMut(usize, f32),
+{
+    let screen = points.map(project);
+    // Depth toward the viewer along the isometric view direction.
+    let depths = points.map(|point| point.x + point.y + point.z);
+    let area = edge(screen[0], screen[1], screen[2]);
+    if area.abs() <= f32::EPSILON {
+        return;
+    }
+    let min_x = screen
+        .iter()
+        .map(|p| p.0)
+        .fold(f32::MAX, f32::min)
+        .floor()
+        .max(0.0) as u32;
+    let max_x = screen
+        .iter()
+        .map(|p| p.0)
+        .fold(f32::MIN, f32::max)
+        .ceil()
+        .min(sprite_width.saturating_sub(1) as f32) as u32;
+    let min_y = screen
+        .iter()
+        .map(|p| p.1)
+        .fold(f32::MAX, f32::min)
+        .floor()
+        .max(0.0) as u32;
+    let max_y = screen
+        .iter()
+        .map(|p| p.1)
+        .fold(f32::MIN, f32::max)
+        .ceil()
+        .min(sprite_height.saturating_sub(1) as f32) as u32;
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let p = (x as f32 + 0.5, y as f32 + 0.5);
+            let w0 = edge(screen[1], screen[2], p) / area;
+            let w1 = edge(screen[2], screen[0], p) / area;
+            let w2 = edge(screen[0], screen[1], p) / area;
+            if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                continue;
+            }
+            let depth_value = w0 * depths[0] + w1 * depths[1] + w2 * depths[2];
+            plot((y * sprite_width + x) as usize, depth_value);
+        }
+    }
+}
+
+fn edge(a: (f32, f32), b: (f32, f32), p: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+pub(super) fn dot(a: Vec3, b: Vec3) -> f32 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
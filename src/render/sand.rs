@@ -0,0 +1,118 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig, TransitionOverrides};
+use crate::render::util::{draw_isometric_ground, mix_color, parse_hex_color};
+use spriteforge_assets::{edge_weight_for_mask, uv_from_xy};
+
+pub fn render_sand_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if config.name != "sand" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    render_sand_with_mask(sprite_width, sprite_height, bg, seed, config, 0, None)
+}
+
+pub fn render_sand_transition_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    transition_mask: u8,
+    overrides: Option<&TransitionOverrides>,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if config.name != "sand_transition" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    render_sand_with_mask(
+        sprite_width,
+        sprite_height,
+        bg,
+        seed,
+        config,
+        transition_mask,
+        overrides,
+    )
+}
+
+fn render_sand_with_mask(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    transition_mask: u8,
+    overrides: Option<&TransitionOverrides>,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let palette = sand_palette(config)?;
+    let grain_density =
+        require_field(config.sand_grain_density, "sand_grain_density")?.clamp(0.0, 1.0);
+    let ripple_count = require_field(config.sand_ripple_count, "sand_ripple_count")?;
+    // The wet band is only needed where the sand meets water, i.e. in transition tiles.
+    let mut wet_band = if transition_mask != 0 {
+        require_field(config.sand_wet_band, "sand_wet_band")?.max(0.0)
+    } else {
+        0.0
+    };
+    if let Some(override_band) = overrides.and_then(|overrides| overrides.sand_wet_band) {
+        wet_band = override_band.max(0.0);
+    }
+
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    let mut base = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+    draw_isometric_ground(&mut base, sprite_width, sprite_height, palette[0]);
+
+    let ripple_phase = rng.gen_range(0.0..1.0);
+    let ripple_wobble = rng.gen_range(0.0..std::f32::consts::TAU);
+    let ripple_count = ripple_count as f32;
+    let width = base.width().max(1) as f32;
+    for (x, y, pixel) in base.enumerate_pixels() {
+        if pixel.0[3] == 0 {
+            continue;
+        }
+        let xf = x as f32 / width;
+        let yf = y as f32 / width;
+        let (u, v) = uv_from_xy(xf, yf);
+
+        let mut color = palette[0];
+        if ripple_count > 0.0 {
+            let wave = u * ripple_count
+                + ripple_phase
+                + 0.15 * (v * std::f32::consts::TAU * 1.5 + ripple_wobble).sin();
+            if wave.fract() < 0.12 {
+                color = palette[2];
+            }
+        }
+        if rng.gen_range(0.0..1.0) < grain_density {
+            color = if rng.gen_bool(0.5) { palette[1] } else { palette[2] };
+        }
+
+        if transition_mask != 0 && wet_band > 0.0 {
+            let dryness = edge_weight_for_mask(transition_mask, xf, yf, 0.0, wet_band);
+            color = mix_color(palette[3], color, dryness);
+        }
+        img.put_pixel(x, y, color);
+    }
+
+    Ok(img)
+}
+
+fn sand_palette(config: &TileConfig) -> Result<[Rgba<u8>; 4], String> {
+    let base_hex = require_field(config.sand_base.clone(), "sand_base")?;
+    let grain_hexes = require_field(config.sand_grains.clone(), "sand_grains")?;
+    let wet_hex = require_field(config.sand_wet.clone(), "sand_wet")?;
+    Ok([
+        parse_hex_color(&base_hex)?,
+        parse_hex_color(&grain_hexes[0])?,
+        parse_hex_color(&grain_hexes[1])?,
+        parse_hex_color(&wet_hex)?,
+    ])
+}
@@ -0,0 +1,130 @@
+use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{require_field, TileConfig, TransitionOverrides};
+use crate::render::util::{
+    draw_isometric_ground, mix_color, parse_hex_color, smoothstep, transition_edge_settings,
+};
+use spriteforge_assets::{edge_weight_for_mask, uv_from_xy};
+
+pub fn render_snow_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if config.name != "snow" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    render_snow_with_mask(sprite_width, sprite_height, bg, seed, config, 0, None)
+}
+
+pub fn render_snow_transition_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    transition_mask: u8,
+    overrides: Option<&TransitionOverrides>,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if config.name != "snow_transition" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    render_snow_with_mask(
+        sprite_width,
+        sprite_height,
+        bg,
+        seed,
+        config,
+        transition_mask,
+        overrides,
+    )
+}
+
+fn render_snow_with_mask(
+    sprite_width: u32,
+    sprite_height: u32,
+    bg: Rgba<u8>,
+    seed: u64,
+    config: &TileConfig,
+    transition_mask: u8,
+    overrides: Option<&TransitionOverrides>,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let palette = snow_palette(config)?;
+    let drift_count = require_field(config.snow_drift_count, "snow_drift_count")?;
+    let sparkle_density =
+        require_field(config.snow_sparkle_density, "snow_sparkle_density")?.clamp(0.0, 1.0);
+    let (cutoff, gradient) = if transition_mask != 0 {
+        transition_edge_settings(
+            "snow",
+            (config.snow_edge_cutoff, config.snow_edge_gradient),
+            overrides.map(|overrides| (overrides.snow_edge_cutoff, overrides.snow_edge_gradient)),
+        )?
+    } else {
+        (0.0, 0.0)
+    };
+
+    // Each drift is a soft directional wave in tile uv space.
+    let drifts: Vec<(f32, f32, f32, f32)> = (0..drift_count)
+        .map(|_| {
+            let angle = rng.gen_range(0.0..std::f32::consts::PI);
+            let frequency = rng.gen_range(1.5..4.0) * std::f32::consts::TAU;
+            let phase = rng.gen_range(0.0..std::f32::consts::TAU);
+            let amplitude = rng.gen_range(0.5..1.0);
+            (angle, frequency, phase, amplitude)
+        })
+        .collect();
+    let amplitude_sum: f32 = drifts.iter().map(|drift| drift.3).sum::<f32>().max(0.001);
+
+    let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
+    let mut base = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+    draw_isometric_ground(&mut base, sprite_width, sprite_height, palette[0]);
+
+    let width = base.width().max(1) as f32;
+    for (x, y, pixel) in base.enumerate_pixels() {
+        if pixel.0[3] == 0 {
+            continue;
+        }
+        let xf = x as f32 / width;
+        let yf = y as f32 / width;
+        let (u, v) = uv_from_xy(xf, yf);
+
+        let mut height = 0.0;
+        for (angle, frequency, phase, amplitude) in &drifts {
+            let along = u * angle.cos() + v * angle.sin();
+            height += (along * frequency + phase).sin() * amplitude;
+        }
+        let height = (height / amplitude_sum) * 0.5 + 0.5;
+
+        // Low ground between drifts picks up the blue shadow tint, crests get the highlight.
+        let mut color = mix_color(palette[2], palette[0], smoothstep(0.15, 0.55, height));
+        color = mix_color(color, palette[1], smoothstep(0.65, 0.95, height));
+        if rng.gen_range(0.0..1.0) < sparkle_density * height {
+            color = palette[3];
+        }
+
+        if transition_mask != 0 {
+            let weight = edge_weight_for_mask(transition_mask, xf, yf, cutoff, gradient);
+            color.0[3] = (color.0[3] as f32 * weight).round() as u8;
+        }
+        img.put_pixel(x, y, color);
+    }
+
+    Ok(img)
+}
+
+fn snow_palette(config: &TileConfig) -> Result<[Rgba<u8>; 4], String> {
+    let base_hex = require_field(config.snow_base.clone(), "snow_base")?;
+    let shade_hexes = require_field(config.snow_shades.clone(), "snow_shades")?;
+    let sparkle_hex = require_field(config.snow_sparkle.clone(), "snow_sparkle")?;
+    Ok([
+        parse_hex_color(&base_hex)?,
+        parse_hex_color(&shade_hexes[0])?,
+        parse_hex_color(&shade_hexes[1])?,
+        parse_hex_color(&sparkle_hex)?,
+    ])
+}
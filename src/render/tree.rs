@@ -1,35 +1,142 @@
+use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 use image::{ImageBuffer, Rgba};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 use crate::config::{require_field, TileConfig};
 use crate::render::parse_hex_color;
-use crate::tree::{generate_tree, TreeModel, TreeSettings, Vec3};
+use crate::render::util::{downsample, mix_color, SampleResolve};
+use crate::bush::{generate_bush, BushSettings};
+use crate::tree::{
+    generate_tree, place_attachments, rotate_tree, sway_tree, AttachmentKind, CrownShape,
+    TreeAttachment, TreeModel, TreeSettings, Vec3,
+};
 
+pub const TREE_SEASONS: [&str; 4] = ["summer", "autumn", "bare", "blossom"];
+
+/// Which look of a seed's tree to draw.
+#[derive(Debug, Clone, Copy)]
+pub struct TreeVariant<'a> {
+    pub season: &'a str,
+    /// Index into `tree_growth_stages`; `None` draws the mature tree.
+    pub stage: Option<u32>,
+    pub frame: u32,
+    /// Quarter turns the camera has orbited clockwise around the tree.
+    pub rotation: u32,
+}
+
+impl Default for TreeVariant<'_> {
+    fn default() -> Self {
+        Self {
+            season: "summer",
+            stage: None,
+            frame: 0,
+            rotation: 0,
+        }
+    }
+}
+
+/// Grown models for one tree or bush config at one sprite size, shared by every sheet built
+/// from it. Space colonization dominates the render time, so each seed's mature tree, its
+/// growth stages and its fitted projection are grown once and reused for every season, view
+/// rotation and sway frame.
+#[derive(Default)]
+pub struct TreeModelCache {
+    mature: RefCell<HashMap<u64, Rc<(TreeModel, Projection)>>>,
+    stages: RefCell<HashMap<(u64, u32), Rc<TreeModel>>>,
+}
+
+impl TreeModelCache {
+    /// The mature model for `seed` and the projection fitted over the configured view
+    /// rotations.
+    fn mature(
+        &self,
+        config: &TileConfig,
+        seed: u64,
+        sprite_width: u32,
+        sprite_height: u32,
+    ) -> Result<Rc<(TreeModel, Projection)>, String> {
+        if let Some(grown) = self.mature.borrow().get(&seed) {
+            return Ok(grown.clone());
+        }
+        let model = plant_model(config, seed)?;
+        let rotations = config.tree_view_rotations.clone().unwrap_or_else(|| vec![0]);
+        let views: Vec<TreeModel> = rotations
+            .iter()
+            .map(|turns| rotate_tree(&model, *turns))
+            .collect();
+        let projection = build_projection(&views, sprite_width, sprite_height);
+        let grown = Rc::new((model, projection));
+        self.mature.borrow_mut().insert(seed, grown.clone());
+        Ok(grown)
+    }
+
+    /// The unrotated model for growth stage `stage` of `seed`.
+    fn stage(
+        &self,
+        config: &TileConfig,
+        seed: u64,
+        stage: u32,
+        mature: &TreeModel,
+    ) -> Result<Rc<TreeModel>, String> {
+        if let Some(grown) = self.stages.borrow().get(&(seed, stage)) {
+            return Ok(grown.clone());
+        }
+        let stages = require_field(config.tree_growth_stages.clone(), "tree_growth_stages")?;
+        let fraction = *stages
+            .get(stage as usize)
+            .ok_or_else(|| format!("Tree growth stage {stage} is not configured"))?;
+        let model = match bush_settings_from_config(config) {
+            Some(settings) => generate_bush(seed, &settings.at_growth(fraction)),
+            None => {
+                let settings = tree_settings_from_config(config)?;
+                generate_tree(seed, &settings.at_growth(fraction, mature.iterations))
+            }
+        };
+        let grown = Rc::new(model);
+        self.stages.borrow_mut().insert((seed, stage), grown.clone());
+        Ok(grown)
+    }
+}
+
+/// Renders one tree variant. The model only depends on the seed, so every season and frame of
+/// a seed shares the same branches and leaf positions, and its growth stages share a layout.
 pub fn render_tree_tile(
     sprite_width: u32,
     sprite_height: u32,
     bg: Rgba<u8>,
     seed: u64,
     config: &TileConfig,
+    cache: &TreeModelCache,
+    variant: TreeVariant,
 ) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
     if config.name != "tree" && config.name != "bush" {
         return Err(format!("Unknown tile name: {}", config.name));
     }
+    let season = variant.season;
+    if !TREE_SEASONS.contains(&season) {
+        return Err(format!("Unknown tree season: {season}"));
+    }
 
-    let settings = tree_settings_from_config(config)?;
-    let model = generate_tree(seed, &settings);
-    let trunk_color = parse_hex_color(&require_field(
-        config.tree_trunk_color.clone(),
-        "tree_trunk_color",
-    )?)?;
-    let leaf_color = parse_hex_color(&require_field(
-        config.tree_leaf_color.clone(),
-        "tree_leaf_color",
-    )?)?;
+    let (rest, model, projection) =
+        tree_models(config, cache, seed, variant, sprite_width, sprite_height)?;
+    let supersample = Supersample::from_config(config)?;
+    let projection = projection.supersampled(supersample.factor);
+    let (sprite_width, sprite_height) = supersample.size(sprite_width, sprite_height);
+    let (trunk_color, _) = tree_colors_from_config(config)?;
+    let foliage = Foliage::from_config(config, season, seed, &rest)?;
+    let attachments = attachments_from_config(config, seed, &rest)?;
+    let light = BakedLight::from_config(config)?;
+    let lit = |color: Rgba<u8>, normal: Vec3| match &light {
+        Some(light) => light.shade(color, normal),
+        None => color,
+    };
 
     let mut tile = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
-    let projection = build_projection(&model, sprite_width, sprite_height);
     let project = |point: Vec3| -> (i32, i32) {
         let (screen_x, screen_y) = projection.project(point);
         (screen_x.round() as i32, screen_y.round() as i32)
@@ -43,11 +150,13 @@ pub fn render_tree_tile(
             start: segment.start,
             end: segment.end,
             radius: segment.radius,
+            normal: segment.normal,
         });
     }
-    for stem in &model.leaf_stems {
+    for (index, stem) in model.leaf_stems.iter().enumerate() {
         let depth = (stem.start.x + stem.start.y + stem.end.x + stem.end.y) * 0.5;
         draw_items.push(DrawItem::LeafStem {
+            index,
             depth,
             start: stem.start,
             end: stem.end,
@@ -55,6 +164,9 @@ pub fn render_tree_tile(
         });
     }
     for (idx, leaf) in model.leaves.iter().enumerate() {
+        if season == "bare" {
+            break;
+        }
         let depth = leaf.position.x + leaf.position.y;
         let angle = model
             .leaf_stems
@@ -68,12 +180,25 @@ pub fn render_tree_tile(
             })
             .unwrap_or(0.0);
         draw_items.push(DrawItem::Leaf {
+            index: idx,
             depth,
             position: leaf.position,
             radius: leaf.size,
             angle,
         });
     }
+    for (index, (attachment, _)) in attachments.iter().enumerate() {
+        if season == "bare" && attachment.kind.on_leaf() {
+            continue;
+        }
+        let (position, normal) = attachment.pose(&model);
+        draw_items.push(DrawItem::Attachment {
+            index,
+            depth: position.x + position.y,
+            position,
+            normal,
+        });
+    }
 
     draw_items.sort_by(|a, b| a.depth().partial_cmp(&b.depth()).unwrap_or(Ordering::Equal));
     for item in draw_items {
@@ -82,14 +207,16 @@ pub fn render_tree_tile(
                 start,
                 end,
                 radius,
+                normal,
                 ..
             } => {
                 let (x0, y0) = project(start);
                 let (x1, y1) = project(end);
                 let radius = (radius * projection.scale).round().max(1.0) as i32;
-                draw_thick_line(&mut tile, x0, y0, x1, y1, radius, trunk_color);
+                draw_thick_line(&mut tile, x0, y0, x1, y1, radius, lit(trunk_color, normal));
             }
             DrawItem::Leaf {
+                index,
                 position,
                 radius,
                 angle,
@@ -98,9 +225,16 @@ pub fn render_tree_tile(
                 let (x, y) = project(position);
                 let rx = (radius * projection.scale).round().max(1.0) as i32;
                 let ry = (radius * projection.scale * 0.7).round().max(1.0) as i32;
-                draw_filled_oval_rotated(&mut tile, x, y, rx, ry, angle, leaf_color);
+                let leaf = &foliage.leaves[index];
+                let normal = model.leaves[index].normal;
+                draw_filled_oval_rotated(&mut tile, x, y, rx, ry, angle, lit(leaf.color, normal));
+                if let Some(blossom) = leaf.blossom {
+                    let r = (rx as f32 * 0.45).round().max(1.0) as i32;
+                    draw_filled_circle(&mut tile, x, y - ry / 3, r, lit(blossom, normal));
+                }
             }
             DrawItem::LeafStem {
+                index,
                 start,
                 end,
                 radius,
@@ -109,12 +243,279 @@ pub fn render_tree_tile(
                 let (x0, y0) = project(start);
                 let (x1, y1) = project(end);
                 let radius = (radius * projection.scale).round().max(1.0) as i32;
-                draw_thick_line(&mut tile, x0, y0, x1, y1, radius, trunk_color);
+                let color = lit(trunk_color, model.leaf_stems[index].normal);
+                draw_thick_line(&mut tile, x0, y0, x1, y1, radius, color);
+                if season == "bare" {
+                    // Bare stems fork into a pair of twigs where the leaf would hang.
+                    let spread = foliage.leaves[index].twig_spread;
+                    let (dx, dy) = ((x1 - x0) as f32, (y1 - y0) as f32);
+                    for side in [-spread, spread] {
+                        let (sin, cos) = side.sin_cos();
+                        let tip_x = x1 + ((dx * cos - dy * sin) * 0.6).round() as i32;
+                        let tip_y = y1 + ((dx * sin + dy * cos) * 0.6).round() as i32;
+                        let twig = supersample.factor as i32;
+                        draw_thick_line(&mut tile, x1, y1, tip_x, tip_y, twig, color);
+                    }
+                }
+            }
+            DrawItem::Attachment {
+                index,
+                position,
+                normal,
+                ..
+            } => {
+                let (attachment, color) = &attachments[index];
+                let (x, y) = project(position);
+                let r = (attachment.size * projection.scale).round().max(1.0) as i32;
+                let color = lit(*color, normal);
+                match attachment.kind {
+                    AttachmentKind::Nest => {
+                        // A twig bowl: the rim oval with its darker hollow showing above.
+                        let ry = (r as f32 * 0.55).round().max(1.0) as i32;
+                        draw_filled_oval_rotated(&mut tile, x, y, r, ry, 0.0, color);
+                        let hollow = mix_color(color, Rgba([0, 0, 0, 255]), 0.45);
+                        let (hx, hy) = ((r as f32 * 0.6) as i32, (ry as f32 * 0.45) as i32);
+                        draw_filled_oval_rotated(&mut tile, x, y - ry / 3, hx, hy, 0.0, hollow);
+                    }
+                    AttachmentKind::Blossom => {
+                        draw_filled_circle(&mut tile, x, y, r, color);
+                        let heart = mix_color(color, Rgba([247, 214, 74, 255]), 0.7);
+                        draw_filled_circle(&mut tile, x, y, (r / 3).max(1), heart);
+                    }
+                    _ => {
+                        draw_filled_circle(&mut tile, x, y, r, color);
+                        if r >= 3 {
+                            let shine = mix_color(color, Rgba([255, 255, 255, 255]), 0.45);
+                            draw_filled_circle(&mut tile, x - r / 3, y - r / 3, r / 4, shine);
+                        }
+                    }
+                }
             }
         }
     }
 
-    Ok(tile)
+    Ok(supersample.resolve(tile))
+}
+
+/// Optional supersampling: the color sprite and mask are drawn `factor` times larger and
+/// resolved down the same way, so thin branches keep partial coverage instead of breaking up.
+struct Supersample {
+    factor: u32,
+    resolve: SampleResolve,
+}
+
+impl Supersample {
+    fn from_config(config: &TileConfig) -> Result<Self, String> {
+        let factor = config.tree_supersample.unwrap_or(1);
+        if ![1, 2, 4, 8].contains(&factor) {
+            return Err(format!("tree_supersample must be 1, 2, 4 or 8, got {factor}"));
+        }
+        if factor == 1 {
+            return Ok(Self {
+                factor,
+                resolve: SampleResolve::Coverage,
+            });
+        }
+        let resolve = match require_field(
+            config.tree_supersample_resolve.clone(),
+            "tree_supersample_resolve",
+        )?
+        .as_str()
+        {
+            "coverage" => SampleResolve::Coverage,
+            "majority" => SampleResolve::Majority,
+            other => return Err(format!("Unknown tree_supersample_resolve: {other}")),
+        };
+        Ok(Self { factor, resolve })
+    }
+
+    fn size(&self, sprite_width: u32, sprite_height: u32) -> (u32, u32) {
+        (sprite_width * self.factor, sprite_height * self.factor)
+    }
+
+    fn resolve(&self, image: ImageBuffer<Rgba<u8>, Vec<u8>>) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        if self.factor == 1 {
+            return image;
+        }
+        downsample(&image, self.factor, self.resolve)
+    }
+}
+
+/// Lambert plus ambient shading baked into the color sprite with the normals the mask encodes,
+/// matching `tree_light.wgsl` for engines without the shader.
+struct BakedLight {
+    direction: Vec3,
+    ambient: f32,
+    diffuse: f32,
+    /// Toon bands the diffuse term snaps to; 0 keeps it smooth.
+    bands: u32,
+}
+
+impl BakedLight {
+    fn from_config(config: &TileConfig) -> Result<Option<Self>, String> {
+        if !config.tree_bake_light.unwrap_or(false) {
+            return Ok(None);
+        }
+        let [x, y, z] = require_field(config.tree_light_direction, "tree_light_direction")?;
+        Ok(Some(Self {
+            direction: Vec3::new(x, y, z).normalized(),
+            ambient: require_field(config.tree_light_ambient, "tree_light_ambient")?,
+            diffuse: require_field(config.tree_light_diffuse, "tree_light_diffuse")?,
+            bands: require_field(config.tree_light_bands, "tree_light_bands")?,
+        }))
+    }
+
+    fn shade(&self, color: Rgba<u8>, normal: Vec3) -> Rgba<u8> {
+        let light = self.direction;
+        let mut ndotl = (normal.x * light.x + normal.y * light.y + normal.z * light.z).max(0.0);
+        if self.bands > 0 {
+            let bands = self.bands as f32;
+            ndotl = (ndotl * bands).ceil() / bands;
+        }
+        let shade = (self.ambient + ndotl * self.diffuse).clamp(0.0, 1.0);
+        let [r, g, b, a] = color.0;
+        let scale = |channel: u8| (channel as f32 * shade).round() as u8;
+        Rgba([scale(r), scale(g), scale(b), a])
+    }
+}
+
+/// Per-leaf colors for one season, drawn from a separate stream so the tree shape and every
+/// leaf's random picks stay the same across seasons.
+struct Foliage {
+    leaves: Vec<LeafLook>,
+}
+
+struct LeafLook {
+    color: Rgba<u8>,
+    blossom: Option<Rgba<u8>>,
+    twig_spread: f32,
+}
+
+impl Foliage {
+    fn from_config(
+        config: &TileConfig,
+        season: &str,
+        seed: u64,
+        model: &TreeModel,
+    ) -> Result<Self, String> {
+        let palette = leaf_palette_from_config(config)?;
+        let jitter = config.tree_leaf_jitter.unwrap_or(0.0);
+        let autumn = if season == "autumn" {
+            let colors = parse_colors(
+                &require_field(config.tree_autumn_palette.clone(), "tree_autumn_palette")?,
+                "tree_autumn_palette",
+            )?;
+            Some((colors, require_field(config.tree_autumn_mix, "tree_autumn_mix")?))
+        } else {
+            None
+        };
+        let blossom = if season == "blossom" {
+            let colors = parse_colors(
+                &require_field(config.tree_blossom_colors.clone(), "tree_blossom_colors")?,
+                "tree_blossom_colors",
+            )?;
+            let density = require_field(config.tree_blossom_density, "tree_blossom_density")?;
+            Some((colors, density))
+        } else {
+            None
+        };
+
+        let (low, high) = model
+            .leaves
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(low, high), leaf| {
+                (low.min(leaf.position.z), high.max(leaf.position.z))
+            });
+        let light = Vec3::new(-0.5, 0.3, 0.8).normalized();
+        let mut rng = StdRng::seed_from_u64(seed ^ 0x1eaf_c010);
+        let leaves = model
+            .leaves
+            .iter()
+            .map(|leaf| {
+                // Draw every roll up front so each leaf uses the same numbers in any season.
+                let shift = rng.gen_range(-0.5..0.5) * jitter;
+                let turn = rng.gen_range(0.0..1.0);
+                let bloom = rng.gen_range(0.0..1.0);
+                let pick = rng.gen_range(0.0..1.0);
+                let twig_spread = rng.gen_range(0.4..0.8);
+                // Higher leaves and leaves facing the light sit further up the gradient.
+                let height = (leaf.position.z - low) / (high - low).max(0.001);
+                let facing = leaf.normal.x * light.x
+                    + leaf.normal.y * light.y
+                    + leaf.normal.z * light.z;
+                let t = (0.55 * height + 0.45 * (facing * 0.5 + 0.5) + shift).clamp(0.0, 1.0);
+                let color = match &autumn {
+                    Some((colors, mix)) if turn < *mix => sample_gradient(colors, t),
+                    _ => sample_gradient(&palette, t),
+                };
+                let blossom = match &blossom {
+                    Some((colors, density)) if bloom < *density => {
+                        Some(colors[(pick * colors.len() as f32) as usize % colors.len()])
+                    }
+                    _ => None,
+                };
+                LeafLook {
+                    color,
+                    blossom,
+                    twig_spread,
+                }
+            })
+            .collect();
+        Ok(Self { leaves })
+    }
+}
+
+fn parse_colors(hexes: &[String], field: &str) -> Result<Vec<Rgba<u8>>, String> {
+    if hexes.is_empty() {
+        return Err(format!("{field} must list at least one color"));
+    }
+    hexes.iter().map(|hex| parse_hex_color(hex)).collect()
+}
+
+/// The `tree_attachments` placed on `model` with their colors, from a stream of their own so
+/// they sit on the same anchors in every season and frame.
+fn attachments_from_config(
+    config: &TileConfig,
+    seed: u64,
+    model: &TreeModel,
+) -> Result<Vec<(TreeAttachment, Rgba<u8>)>, String> {
+    let kinds = config.tree_attachments.clone().unwrap_or_default();
+    if kinds.is_empty() {
+        return Ok(Vec::new());
+    }
+    let counts = require_field(config.tree_attachment_counts.clone(), "tree_attachment_counts")?;
+    let sizes = require_field(config.tree_attachment_sizes.clone(), "tree_attachment_sizes")?;
+    let colors = parse_colors(
+        &require_field(config.tree_attachment_colors.clone(), "tree_attachment_colors")?,
+        "tree_attachment_colors",
+    )?;
+    if counts.len() != kinds.len() || sizes.len() != kinds.len() || colors.len() != kinds.len()
+    {
+        return Err(
+            "tree_attachment_counts, sizes and colors must each list one entry per attachment"
+                .to_string(),
+        );
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed ^ 0xa77a_c4ed);
+    let mut attachments = Vec::new();
+    for (index, kind) in kinds.iter().enumerate() {
+        let kind = AttachmentKind::from_name(kind)?;
+        for attachment in place_attachments(model, kind, counts[index], sizes[index], &mut rng) {
+            attachments.push((attachment, colors[index]));
+        }
+    }
+    Ok(attachments)
+}
+
+/// Color at `t` along evenly spaced gradient stops.
+fn sample_gradient(stops: &[Rgba<u8>], t: f32) -> Rgba<u8> {
+    if stops.len() == 1 {
+        return stops[0];
+    }
+    let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let index = (position.floor() as usize).min(stops.len() - 2);
+    mix_color(stops[index], stops[index + 1], position - index as f32)
 }
 
 pub fn render_tree_mask_tile(
@@ -122,17 +523,146 @@ pub fn render_tree_mask_tile(
     sprite_height: u32,
     seed: u64,
     config: &TileConfig,
+    cache: &TreeModelCache,
+    variant: TreeVariant,
 ) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    let supersample = Supersample::from_config(config)?;
+    let (canvas, _) =
+        rasterize_tree_surface(
+            sprite_width,
+            sprite_height,
+            seed,
+            config,
+            cache,
+            variant,
+            &supersample,
+        )?;
+    let mut mask = supersample.resolve(canvas.mask);
+    if supersample.factor > 1 {
+        // Blended normals come out shorter than unit length.
+        for pixel in mask.pixels_mut().filter(|pixel| pixel[3] > 0) {
+            let decode = |channel: u8| channel as f32 / 255.0 * 2.0 - 1.0;
+            let normal = Vec3::new(decode(pixel[0]), decode(pixel[1]), decode(pixel[2]));
+            let [r, g, b, _] = encode_normal(normal.normalized()).0;
+            *pixel = Rgba([r, g, b, pixel[3]]);
+        }
+    }
+    Ok(mask)
+}
+
+/// Fixed point steps per sprite pixel in the depth sheet's channels.
+pub const DEPTH_UNITS_PER_PIXEL: f32 = 64.0;
+/// Added to the isometric depth channel so surfaces behind the ground point stay positive.
+pub const DEPTH_BIAS: u16 = 32768;
+
+/// Renders a 16-bit depth tile for a tree variant, aligned with its color sprite. Red holds
+/// each pixel's height above the ground plane and green its isometric depth, the screen-space
+/// distance of the pixel's ground footprint below the tree's base plus `DEPTH_BIAS`, both in
+/// `DEPTH_UNITS_PER_PIXEL` steps. A character standing at the same depth or further down
+/// the screen is in front of the pixel. Alpha is opaque wherever the tree covers the pixel.
+pub fn render_tree_depth_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    seed: u64,
+    config: &TileConfig,
+    cache: &TreeModelCache,
+    variant: TreeVariant,
+) -> Result<ImageBuffer<Rgba<u16>, Vec<u16>>, String> {
+    let supersample = Supersample::from_config(config)?;
+    let (canvas, scale) =
+        rasterize_tree_surface(
+            sprite_width,
+            sprite_height,
+            seed,
+            config,
+            cache,
+            variant,
+            &supersample,
+        )?;
+    Ok(depth_tile(&canvas, scale, supersample.factor))
+}
+
+/// Resolves a canvas drawn `factor` times larger into depth tile pixels; `scale` is the
+/// unsupersampled projection scale.
+fn depth_tile(canvas: &NormalCanvas, scale: f32, factor: u32) -> ImageBuffer<Rgba<u16>, Vec<u16>> {
+    let (canvas_width, canvas_height) = canvas.mask.dimensions();
+    let (sprite_width, sprite_height) = (canvas_width / factor, canvas_height / factor);
+    let mut tile = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+    for (x, y, pixel) in tile.enumerate_pixels_mut() {
+        // The nearest covered sample wins, so occlusion errs toward hiding characters.
+        let nearest = (0..factor * factor)
+            .map(|sample| {
+                let sx = x * factor + sample % factor;
+                let sy = y * factor + sample / factor;
+                (sy * canvas_width + sx) as usize
+            })
+            .filter(|&index| canvas.depth[index] > f32::NEG_INFINITY)
+            .max_by(|&a, &b| canvas.depth[a].total_cmp(&canvas.depth[b]));
+        let Some(index) = nearest else {
+            continue;
+        };
+        let fixed = |pixels: f32| (pixels * DEPTH_UNITS_PER_PIXEL).round();
+        let height = fixed(canvas.height[index].max(0.0) * scale).min(u16::MAX as f32);
+        let depth = (fixed(canvas.depth[index] * 0.5 * scale) + DEPTH_BIAS as f32)
+            .clamp(0.0, u16::MAX as f32);
+        *pixel = Rgba([height as u16, depth as u16, 0, u16::MAX]);
+    }
+    tile
+}
+
+/// Per-pixel normals, isometric depth (`x + y`) and height (`z`) of a tree variant's nearest
+/// surface, drawn at the supersampled size.
+struct NormalCanvas {
+    mask: ImageBuffer<Rgba<u8>, Vec<u8>>,
+    depth: Vec<f32>,
+    height: Vec<f32>,
+}
+
+impl NormalCanvas {
+    fn new(width: u32, height: u32) -> Self {
+        let pixels = (width * height) as usize;
+        Self {
+            mask: ImageBuffer::from_pixel(width, height, Rgba([0, 0, 0, 0])),
+            depth: vec![f32::NEG_INFINITY; pixels],
+            height: vec![0.0; pixels],
+        }
+    }
+
+    fn plot(&mut self, x: u32, y: u32, depth: f32, height: f32, normal: Vec3) {
+        let index = (y * self.mask.width() + x) as usize;
+        if depth <= self.depth[index] {
+            return;
+        }
+        self.depth[index] = depth;
+        self.height[index] = height;
+        self.mask.put_pixel(x, y, encode_normal(normal));
+    }
+}
+
+/// Rasterizes the variant's surface for the mask and depth sheets. Also returns the
+/// projection scale of the unsupersampled sprite, in pixels per world unit.
+fn rasterize_tree_surface(
+    sprite_width: u32,
+    sprite_height: u32,
+    seed: u64,
+    config: &TileConfig,
+    cache: &TreeModelCache,
+    variant: TreeVariant,
+    supersample: &Supersample,
+) -> Result<(NormalCanvas, f32), String> {
     if config.name != "tree" && config.name != "bush" {
         return Err(format!("Unknown tile name: {}", config.name));
     }
+    let season = variant.season;
 
-    let settings = tree_settings_from_config(config)?;
-    let model = generate_tree(seed, &settings);
-    let projection = build_projection(&model, sprite_width, sprite_height);
+    let (rest, model, projection) =
+        tree_models(config, cache, seed, variant, sprite_width, sprite_height)?;
+    let attachments = attachments_from_config(config, seed, &rest)?;
+    let scale = projection.scale;
+    let projection = projection.supersampled(supersample.factor);
+    let (sprite_width, sprite_height) = supersample.size(sprite_width, sprite_height);
 
-    let mut mask = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
-    let mut depth = vec![f32::NEG_INFINITY; (sprite_width * sprite_height) as usize];
+    let mut canvas = NormalCanvas::new(sprite_width, sprite_height);
 
     for segment in &model.segments {
         let dir = Vec3::new(
@@ -153,10 +683,7 @@ pub fn render_tree_mask_tile(
             let depth_value = point.x + point.y;
             rasterize_normal_sphere(
                 &projection,
-                &mut mask,
-                &mut depth,
-                sprite_width,
-                sprite_height,
+                &mut canvas,
                 point,
                 segment.radius,
                 depth_value,
@@ -184,10 +711,7 @@ pub fn render_tree_mask_tile(
             let depth_value = point.x + point.y;
             rasterize_normal_sphere(
                 &projection,
-                &mut mask,
-                &mut depth,
-                sprite_width,
-                sprite_height,
+                &mut canvas,
                 point,
                 stem.radius,
                 depth_value,
@@ -197,6 +721,9 @@ pub fn render_tree_mask_tile(
     }
 
     for (idx, leaf) in model.leaves.iter().enumerate() {
+        if season == "bare" {
+            break;
+        }
         let depth_value = leaf.position.x + leaf.position.y;
         let angle = model
             .leaf_stems
@@ -213,23 +740,195 @@ pub fn render_tree_mask_tile(
         let ry = (leaf.size * projection.scale * 0.7).max(1.0);
         rasterize_normal_oval_rotated(
             &projection,
-            &mut mask,
-            &mut depth,
-            sprite_width,
-            sprite_height,
+            &mut canvas,
             leaf.position,
-            rx,
-            ry,
+            (rx, ry),
             angle,
             depth_value,
             leaf.normal,
         );
     }
 
-    Ok(mask)
+    for (attachment, _) in &attachments {
+        if season == "bare" && attachment.kind.on_leaf() {
+            continue;
+        }
+        let (position, normal) = attachment.pose(&model);
+        let depth_value = position.x + position.y;
+        match attachment.kind {
+            AttachmentKind::Nest => {
+                let rx = attachment.size * projection.scale;
+                rasterize_normal_oval_rotated(
+                    &projection,
+                    &mut canvas,
+                    position,
+                    (rx, rx * 0.55),
+                    0.0,
+                    depth_value,
+                    normal,
+                );
+            }
+            AttachmentKind::Blossom => rasterize_normal_sphere(
+                &projection,
+                &mut canvas,
+                position,
+                attachment.size,
+                depth_value,
+                normal,
+            ),
+            _ => rasterize_normal_ball(
+                &projection,
+                &mut canvas,
+                position,
+                attachment.size,
+                depth_value,
+            ),
+        }
+    }
+
+    Ok((canvas, scale))
+}
+
+/// Renders the soft shadow a tree variant casts on the z=0 ground along `tree_shadow_light`,
+/// using the tree tile's projection so the shadow sheet lines up with the tree sheet.
+pub fn render_tree_shadow_tile(
+    sprite_width: u32,
+    sprite_height: u32,
+    seed: u64,
+    config: &TileConfig,
+    cache: &TreeModelCache,
+    variant: TreeVariant,
+) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
+    if config.name != "tree" && config.name != "bush" {
+        return Err(format!("Unknown tile name: {}", config.name));
+    }
+    let [lx, ly, lz] = require_field(config.tree_shadow_light, "tree_shadow_light")?;
+    let light = Vec3::new(lx, ly, lz).normalized();
+    if light.z <= 0.05 {
+        return Err("tree_shadow_light must point up toward the sky".to_string());
+    }
+    let opacity = require_field(config.tree_shadow_opacity, "tree_shadow_opacity")?;
+    let blur = require_field(config.tree_shadow_blur, "tree_shadow_blur")?;
+
+    let (rest, model, projection) =
+        tree_models(config, cache, seed, variant, sprite_width, sprite_height)?;
+    let attachments = attachments_from_config(config, seed, &rest)?;
+    let mut shadow = ImageBuffer::from_pixel(sprite_width, sprite_height, Rgba([0, 0, 0, 0]));
+    // A ground circle of radius r shows as an r*sqrt(2) by r/sqrt(2) ellipse.
+    let mut cast = |point: Vec3, radius: f32| {
+        let ground = point - light * (point.z.max(0.0) / light.z);
+        let ground = Vec3::new(ground.x, ground.y, 0.0);
+        let (x, y) = projection.project(ground);
+        let rx = (radius * projection.scale * std::f32::consts::SQRT_2).round() as i32;
+        let ry = (radius * projection.scale * std::f32::consts::FRAC_1_SQRT_2).round() as i32;
+        let (x, y) = (x.round() as i32, y.round() as i32);
+        draw_filled_oval_rotated(&mut shadow, x, y, rx, ry, 0.0, Rgba([0, 0, 0, 255]));
+    };
+    let branches = model
+        .segments
+        .iter()
+        .map(|segment| (segment.start, segment.end, segment.radius))
+        .chain(
+            model
+                .leaf_stems
+                .iter()
+                .map(|stem| (stem.start, stem.end, stem.radius)),
+        );
+    for (start, end, radius) in branches {
+        let length = (end - start).length().max(0.001);
+        let steps = (length / (radius * 0.75).max(0.05)).ceil().max(1.0) as i32;
+        for i in 0..=steps {
+            cast(start + (end - start) * (i as f32 / steps as f32), radius);
+        }
+    }
+    if variant.season != "bare" {
+        for leaf in &model.leaves {
+            cast(leaf.position, leaf.size);
+        }
+    }
+    for (attachment, _) in &attachments {
+        if variant.season != "bare" || !attachment.kind.on_leaf() {
+            cast(attachment.pose(&model).0, attachment.size);
+        }
+    }
+
+    let mut shadow = image::imageops::blur(&shadow, blur);
+    for pixel in shadow.pixels_mut() {
+        *pixel = Rgba([0, 0, 0, (pixel[3] as f32 * opacity).round() as u8]);
+    }
+    Ok(shadow)
+}
+
+/// The variant's model at rest, the same model posed for its wind sway frame, and the
+/// projection of the mature tree. Leaf colors come from the rest model so they hold still
+/// across frames, and sharing the mature projection keeps frames and growth stages aligned.
+/// The model is turned for the view before it sways, so wind and light keep their screen
+/// directions in every rotation, and the projection fits every configured rotation so a tree
+/// keeps its scale and ground point as the camera turns.
+fn tree_models(
+    config: &TileConfig,
+    cache: &TreeModelCache,
+    seed: u64,
+    variant: TreeVariant,
+    sprite_width: u32,
+    sprite_height: u32,
+) -> Result<(TreeModel, TreeModel, Projection), String> {
+    if variant.rotation > 3 {
+        return Err(format!("Tree rotation {} is not a quarter turn of 0-3", variant.rotation));
+    }
+    let mature = cache.mature(config, seed, sprite_width, sprite_height)?;
+    let (mature, projection) = (&mature.0, mature.1);
+    let model = match variant.stage {
+        Some(stage) => rotate_tree(&*cache.stage(config, seed, stage, mature)?, variant.rotation),
+        None => rotate_tree(mature, variant.rotation),
+    };
+    let frames = config.tree_sway_frames.unwrap_or(1);
+    if frames <= 1 {
+        return Ok((model.clone(), model, projection));
+    }
+    let frame = variant.frame;
+    if frame >= frames {
+        return Err(format!("Tree frame {frame} is out of range for {frames} frames"));
+    }
+    let amplitude = require_field(config.tree_sway_amplitude, "tree_sway_amplitude")?;
+    let posed = sway_tree(&model, amplitude.to_radians(), frame as f32 / frames as f32);
+    Ok((model, posed, projection))
+}
+
+/// The mature model for `seed`: a shrub for bush configs that set any `bush_*` field and a
+/// space colonization tree otherwise.
+pub fn plant_model(config: &TileConfig, seed: u64) -> Result<TreeModel, String> {
+    if let Some(settings) = bush_settings_from_config(config) {
+        return Ok(generate_bush(seed, &settings));
+    }
+    Ok(generate_tree(seed, &tree_settings_from_config(config)?))
 }
 
-fn tree_settings_from_config(config: &TileConfig) -> Result<TreeSettings, String> {
+pub fn tree_colors_from_config(config: &TileConfig) -> Result<(Rgba<u8>, Rgba<u8>), String> {
+    let trunk_color = parse_hex_color(&require_field(
+        config.tree_trunk_color.clone(),
+        "tree_trunk_color",
+    )?)?;
+    let leaf_color = parse_hex_color(&require_field(
+        config.tree_leaf_color.clone(),
+        "tree_leaf_color",
+    )?)?;
+    Ok((trunk_color, leaf_color))
+}
+
+/// The `tree_leaf_palette`, or just `tree_leaf_color` when no palette is set, so a config
+/// without one renders the same leaf color that mesh export uses.
+fn leaf_palette_from_config(config: &TileConfig) -> Result<Vec<Rgba<u8>>, String> {
+    match &config.tree_leaf_palette {
+        Some(hexes) => parse_colors(hexes, "tree_leaf_palette"),
+        None => {
+            let (_, leaf_color) = tree_colors_from_config(config)?;
+            Ok(vec![leaf_color])
+        }
+    }
+}
+
+pub fn tree_settings_from_config(config: &TileConfig) -> Result<TreeSettings, String> {
     Ok(TreeSettings {
         trunk_height: require_field(config.tree_trunk_height, "tree_trunk_height")?,
         crown_radius: require_field(config.tree_crown_radius, "tree_crown_radius")?,
@@ -246,9 +945,44 @@ fn tree_settings_from_config(config: &TileConfig) -> Result<TreeSettings, String
         leaf_size: require_field(config.tree_leaf_size, "tree_leaf_size")?,
         max_leaves: require_field(config.tree_leaf_count, "tree_leaf_count")?,
         leaf_normal_bias: require_field(config.tree_leaf_normal_bias, "tree_leaf_normal_bias")?,
+        crown_shape: match config.tree_crown_shape.as_deref() {
+            Some(name) => CrownShape::from_name(name)?,
+            None => CrownShape::Cylinder,
+        },
+        tropism: config.tree_tropism.unwrap_or(0.0),
+        lean: config.tree_lean.unwrap_or(0.0).to_radians(),
     })
 }
 
+/// Shrub settings for a bush config, with unset `bush_*` fields taken from the defaults.
+/// `None` for trees and for bush configs written before the shrub generator, which keep
+/// growing bushes through space colonization from their `tree_*` fields.
+fn bush_settings_from_config(config: &TileConfig) -> Option<BushSettings> {
+    let uses_shrub = config.bush_radius.is_some()
+        || config.bush_height.is_some()
+        || config.bush_stems.is_some()
+        || config.bush_stem_radius.is_some()
+        || config.bush_clusters.is_some()
+        || config.bush_cluster_radius.is_some()
+        || config.bush_cluster_leaves.is_some()
+        || config.bush_leaf_size.is_some();
+    if config.name != "bush" || !uses_shrub {
+        return None;
+    }
+    let defaults = BushSettings::default();
+    Some(BushSettings {
+        radius: config.bush_radius.unwrap_or(defaults.radius),
+        height: config.bush_height.unwrap_or(defaults.height),
+        stems: config.bush_stems.unwrap_or(defaults.stems),
+        stem_radius: config.bush_stem_radius.unwrap_or(defaults.stem_radius),
+        clusters: config.bush_clusters.unwrap_or(defaults.clusters),
+        cluster_radius: config.bush_cluster_radius.unwrap_or(defaults.cluster_radius),
+        cluster_leaves: config.bush_cluster_leaves.unwrap_or(defaults.cluster_leaves),
+        leaf_size: config.bush_leaf_size.unwrap_or(defaults.leaf_size),
+    })
+}
+
+#[derive(Clone, Copy)]
 struct Projection {
     scale: f32,
     offset_x: f32,
@@ -261,19 +995,28 @@ enum DrawItem {
         start: Vec3,
         end: Vec3,
         radius: f32,
+        normal: Vec3,
     },
     Leaf {
+        index: usize,
         depth: f32,
         position: Vec3,
         radius: f32,
         angle: f32,
     },
     LeafStem {
+        index: usize,
         depth: f32,
         start: Vec3,
         end: Vec3,
         radius: f32,
     },
+    Attachment {
+        index: usize,
+        depth: f32,
+        position: Vec3,
+        normal: Vec3,
+    },
 }
 
 impl DrawItem {
@@ -282,11 +1025,23 @@ impl DrawItem {
             DrawItem::Segment { depth, .. } => *depth,
             DrawItem::Leaf { depth, .. } => *depth,
             DrawItem::LeafStem { depth, .. } => *depth,
+            DrawItem::Attachment { depth, .. } => *depth,
         }
     }
 }
 
 impl Projection {
+    /// The same projection onto a canvas `factor` times larger, keeping pixel centers aligned.
+    fn supersampled(&self, factor: u32) -> Self {
+        let factor = factor as f32;
+        let center = (factor - 1.0) * 0.5;
+        Self {
+            scale: self.scale * factor,
+            offset_x: self.offset_x * factor + center,
+            offset_y: self.offset_y * factor + center,
+        }
+    }
+
     fn project(&self, point: Vec3) -> (f32, f32) {
         let (raw_x, raw_y) = project_raw(point);
         (
@@ -296,13 +1051,13 @@ impl Projection {
     }
 }
 
-fn build_projection(model: &TreeModel, sprite_width: u32, sprite_height: u32) -> Projection {
+fn build_projection(models: &[TreeModel], sprite_width: u32, sprite_height: u32) -> Projection {
     let mut min_x = f32::MAX;
     let mut max_x = f32::MIN;
     let mut min_y = f32::MAX;
     let mut max_y = f32::MIN;
 
-    for segment in &model.segments {
+    for segment in models.iter().flat_map(|model| &model.segments) {
         expand_bounds(
             segment.start,
             segment.radius,
@@ -321,7 +1076,7 @@ fn build_projection(model: &TreeModel, sprite_width: u32, sprite_height: u32) ->
         );
     }
 
-    for leaf in &model.leaves {
+    for leaf in models.iter().flat_map(|model| &model.leaves) {
         expand_bounds(
             leaf.position,
             leaf.size,
@@ -331,7 +1086,7 @@ fn build_projection(model: &TreeModel, sprite_width: u32, sprite_height: u32) ->
             &mut max_y,
         );
     }
-    for stem in &model.leaf_stems {
+    for stem in models.iter().flat_map(|model| &model.leaf_stems) {
         expand_bounds(
             stem.start,
             stem.radius,
@@ -395,7 +1150,7 @@ fn expand_bounds(
     *max_y = max_y.max(raw_y + r);
 }
 
-fn project_raw(point: Vec3) -> (f32, f32) {
+pub(super) fn project_raw(point: Vec3) -> (f32, f32) {
     let screen_x = point.x - point.y;
     let screen_y = (point.x + point.y) * 0.5 - point.z;
     (screen_x, screen_y)
@@ -403,10 +1158,7 @@ fn project_raw(point: Vec3) -> (f32, f32) {
 
 fn rasterize_normal_sphere(
     projection: &Projection,
-    mask: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
-    depth: &mut [f32],
-    sprite_width: u32,
-    sprite_height: u32,
+    canvas: &mut NormalCanvas,
     center: Vec3,
     radius: f32,
     depth_value: f32,
@@ -421,6 +1173,7 @@ fn rasterize_normal_sphere(
         return;
     }
 
+    let (sprite_width, sprite_height) = canvas.mask.dimensions();
     let min_x = (cx - screen_radius).floor().max(0.0) as i32;
     let max_x = (cx + screen_radius).ceil().min(sprite_width.saturating_sub(1) as f32) as i32;
     let min_y = (cy - screen_radius).floor().max(0.0) as i32;
@@ -433,25 +1186,16 @@ fn rasterize_normal_sphere(
             if dx_screen * dx_screen + dy_screen * dy_screen > screen_radius * screen_radius {
                 continue;
             }
-            let idx = (y as u32 * sprite_width + x as u32) as usize;
-            if depth_value <= depth[idx] {
-                continue;
-            }
-            depth[idx] = depth_value;
-            mask.put_pixel(x as u32, y as u32, encode_normal(normal));
+            canvas.plot(x as u32, y as u32, depth_value, center.z, normal);
         }
     }
 }
 
 fn rasterize_normal_oval_rotated(
     projection: &Projection,
-    mask: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
-    depth: &mut [f32],
-    sprite_width: u32,
-    sprite_height: u32,
+    canvas: &mut NormalCanvas,
     center: Vec3,
-    rx: f32,
-    ry: f32,
+    (rx, ry): (f32, f32),
     angle: f32,
     depth_value: f32,
     normal: Vec3,
@@ -465,6 +1209,7 @@ fn rasterize_normal_oval_rotated(
     let ry2 = ry * ry;
     let bound_x = ((rx2 * cos_a * cos_a + ry2 * sin_a * sin_a).sqrt()).ceil() as i32;
     let bound_y = ((rx2 * sin_a * sin_a + ry2 * cos_a * cos_a).sqrt()).ceil() as i32;
+    let (sprite_width, sprite_height) = canvas.mask.dimensions();
 
     let min_x = (cx - bound_x as f32)
         .floor()
@@ -488,17 +1233,50 @@ fn rasterize_normal_oval_rotated(
             if (local_x * local_x) / rx2 + (local_y * local_y) / ry2 > 1.0 {
                 continue;
             }
-            let idx = (y as u32 * sprite_width + x as u32) as usize;
-            if depth_value <= depth[idx] {
+            canvas.plot(x as u32, y as u32, depth_value, center.z, normal);
+        }
+    }
+}
+
+/// Like `rasterize_normal_sphere`, but each pixel gets the normal of the ball's visible
+/// surface, so round fruit shades as a sphere instead of a flat disc.
+fn rasterize_normal_ball(
+    projection: &Projection,
+    canvas: &mut NormalCanvas,
+    center: Vec3,
+    radius: f32,
+    depth_value: f32,
+) {
+    let (cx, cy) = projection.project(center);
+    let screen_radius = radius * projection.scale;
+    if screen_radius <= 0.0 {
+        return;
+    }
+    // World directions of screen right, screen up and toward the viewer.
+    let right = Vec3::new(1.0, -1.0, 0.0).normalized();
+    let up = Vec3::new(-1.0, -1.0, 2.0).normalized();
+    let view = Vec3::new(1.0, 1.0, 1.0).normalized();
+    let (width, height) = canvas.mask.dimensions();
+    let min_x = (cx - screen_radius).floor().max(0.0) as i32;
+    let max_x = (cx + screen_radius).ceil().min(width.saturating_sub(1) as f32) as i32;
+    let min_y = (cy - screen_radius).floor().max(0.0) as i32;
+    let max_y = (cy + screen_radius).ceil().min(height.saturating_sub(1) as f32) as i32;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let u = (x as f32 - cx) / screen_radius;
+            let v = (cy - y as f32) / screen_radius;
+            let rim = u * u + v * v;
+            if rim > 1.0 {
                 continue;
             }
-            depth[idx] = depth_value;
-            mask.put_pixel(x as u32, y as u32, encode_normal(normal));
+            let normal = right * u + up * v + view * (1.0 - rim).sqrt();
+            canvas.plot(x as u32, y as u32, depth_value, center.z, normal);
         }
     }
 }
 
-fn encode_normal(normal: Vec3) -> Rgba<u8> {
+pub(super) fn encode_normal(normal: Vec3) -> Rgba<u8> {
     let nx = (normal.x * 0.5 + 0.5).clamp(0.0, 1.0);
     let ny = (normal.y * 0.5 + 0.5).clamp(0.0, 1.0);
     let nz = (normal.z * 0.5 + 0.5).clamp(0.0, 1.0);
@@ -605,3 +1383,94 @@ fn put_pixel_safe(
         img.put_pixel(x, y, color);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_tile_encodes_leaf_height_and_leaves_gaps_clear() {
+        let projection = Projection {
+            scale: 8.0,
+            offset_x: 32.0,
+            offset_y: 56.0,
+        };
+        let mut canvas = NormalCanvas::new(64, 64);
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        for z in [1.0, 4.0] {
+            let leaf = Vec3::new(0.0, 0.0, z);
+            rasterize_normal_oval_rotated(&projection, &mut canvas, leaf, (2.0, 2.0), 0.0, 0.0, up);
+        }
+        let tile = depth_tile(&canvas, projection.scale, 1);
+        // The leaves project to (32, 48) and (32, 24).
+        let (low, high) = (tile.get_pixel(32, 48).0, tile.get_pixel(32, 24).0);
+        assert_eq!((low[3], high[3]), (u16::MAX, u16::MAX));
+        assert!(high[0] > low[0] && low[0] > 0);
+        assert_eq!(low[1], DEPTH_BIAS);
+        assert_eq!(tile.get_pixel(32, 36).0[3], 0);
+        assert_eq!(tile.get_pixel(0, 0).0[3], 0);
+    }
+
+    /// A tree config from before crown shapes, seasons and view rotations existed.
+    fn plain_tree_config() -> TileConfig {
+        serde_json::from_str(
+            r##"{
+                "type": "tile",
+                "name": "tree",
+                "tree_trunk_height": 4.2,
+                "tree_crown_radius": 3.8,
+                "tree_crown_height": 5.4,
+                "tree_attraction_points": 300,
+                "tree_segment_length": 0.55,
+                "tree_influence_distance": 2.6,
+                "tree_kill_distance": 0.75,
+                "tree_max_iterations": 240,
+                "tree_base_radius": 0.38,
+                "tree_leaf_size": 0.35,
+                "tree_leaf_count": 500,
+                "tree_leaf_normal_bias": 0.75,
+                "tree_trunk_color": "#5b3a22",
+                "tree_leaf_color": "#3b5f25"
+            }"##,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn crown_shape_tropism_and_lean_default_to_the_upright_cylinder_tree() {
+        let settings = tree_settings_from_config(&plain_tree_config()).unwrap();
+        assert_eq!(settings.crown_shape, CrownShape::Cylinder);
+        assert_eq!((settings.tropism, settings.lean), (0.0, 0.0));
+    }
+
+    #[test]
+    fn cache_grows_each_seed_once_and_fits_only_the_configured_rotations() {
+        let config = plain_tree_config();
+        let cache = TreeModelCache::default();
+        let first = cache.mature(&config, 5, 128, 256).unwrap();
+        let second = cache.mature(&config, 5, 128, 256).unwrap();
+        assert!(Rc::ptr_eq(&first, &second));
+
+        let unrotated = build_projection(std::slice::from_ref(&first.0), 128, 256);
+        let projection = first.1;
+        assert_eq!(
+            (projection.scale, projection.offset_x, projection.offset_y),
+            (unrotated.scale, unrotated.offset_x, unrotated.offset_y)
+        );
+    }
+
+    #[test]
+    fn bush_configs_without_bush_fields_grow_through_space_colonization() {
+        let mut config = plain_tree_config();
+        config.name = "bush".to_string();
+        assert!(bush_settings_from_config(&config).is_none());
+        let model = plant_model(&config, 3).unwrap();
+        let tree = generate_tree(3, &tree_settings_from_config(&config).unwrap());
+        assert_eq!(model.segments.len(), tree.segments.len());
+
+        config.bush_stems = Some(3);
+        let settings = bush_settings_from_config(&config).unwrap();
+        assert_eq!(settings.stems, 3);
+        assert_eq!(settings.radius, BushSettings::default().radius);
+    }
+}
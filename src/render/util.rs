@@ -2,6 +2,8 @@ use image::{ImageBuffer, Rgba};
 use rand::Rng;
 use rand::rngs::StdRng;
 
+use crate::config::{require_field, NoiseConfig};
+
 pub fn parse_hex_color(hex: &str) -> Result<Rgba<u8>, String> {
     let hex = hex.trim().trim_start_matches('#');
     if hex.eq_ignore_ascii_case("transparent") {
@@ -96,6 +98,377 @@ pub fn blit(target: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, src: &ImageBuffer<Rgba<
     }
 }
 
+pub fn mix_color(a: Rgba<u8>, b: Rgba<u8>, t: f32) -> Rgba<u8> {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Rgba([
+        mix(a.0[0], b.0[0]),
+        mix(a.0[1], b.0[1]),
+        mix(a.0[2], b.0[2]),
+        mix(a.0[3], b.0[3]),
+    ])
+}
+
+/// How the samples of a supersampled pixel collapse into one output pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleResolve {
+    /// Alpha is the covered fraction and color the alpha-weighted average.
+    Coverage,
+    /// The most common covered color, kept when at least a quarter of the samples are covered
+    /// so thin lines stay joined. Never invents colors, which suits pixel art.
+    Majority,
+}
+
+/// Shrinks `image` by `factor` on both axes, resolving each `factor` x `factor` block.
+pub fn downsample(
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    factor: u32,
+    resolve: SampleResolve,
+) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    let factor = factor.max(1);
+    let (width, height) = (image.width() / factor, image.height() / factor);
+    let mut samples = Vec::with_capacity((factor * factor) as usize);
+    ImageBuffer::from_fn(width, height, |x, y| {
+        samples.clear();
+        for sy in 0..factor {
+            for sx in 0..factor {
+                samples.push(*image.get_pixel(x * factor + sx, y * factor + sy));
+            }
+        }
+        match resolve {
+            SampleResolve::Coverage => {
+                let mut sums = [0.0f32; 4];
+                for sample in &samples {
+                    let alpha = sample[3] as f32;
+                    for (sum, value) in sums.iter_mut().zip(&sample.0[..3]) {
+                        *sum += *value as f32 * alpha;
+                    }
+                    sums[3] += alpha;
+                }
+                if sums[3] <= 0.0 {
+                    return samples[0];
+                }
+                let color = |channel: usize| (sums[channel] / sums[3]).round() as u8;
+                let alpha = (sums[3] / samples.len() as f32).round() as u8;
+                Rgba([color(0), color(1), color(2), alpha])
+            }
+            SampleResolve::Majority => {
+                let covered: Vec<Rgba<u8>> =
+                    samples.iter().copied().filter(|sample| sample[3] > 0).collect();
+                if covered.len() * 4 < samples.len() {
+                    return samples
+                        .iter()
+                        .copied()
+                        .find(|sample| sample[3] == 0)
+                        .unwrap_or(samples[0]);
+                }
+                // Ties go to the color seen first in row order.
+                let mut best = (covered[0], 0);
+                for candidate in &covered {
+                    let votes = covered.iter().filter(|sample| *sample == candidate).count();
+                    if votes > best.1 {
+                        best = (*candidate, votes);
+                    }
+                }
+                best.0
+            }
+        }
+    })
+}
+
+/// The cutoff and gradient of a `{surface}_transition` edge: the required
+/// `{surface}_edge_cutoff` and `{surface}_edge_gradient` config values, replaced by the
+/// entry's overrides where it sets them.
+pub fn transition_edge_settings(
+    surface: &str,
+    config: (Option<f32>, Option<f32>),
+    overrides: Option<(Option<f32>, Option<f32>)>,
+) -> Result<(f32, f32), String> {
+    let cutoff = require_field(config.0, &format!("{surface}_edge_cutoff"))?;
+    let gradient = require_field(config.1, &format!("{surface}_edge_gradient"))?;
+    let (override_cutoff, override_gradient) = overrides.unwrap_or_default();
+    Ok((
+        override_cutoff.unwrap_or(cutoff).clamp(0.0, 1.0),
+        override_gradient.unwrap_or(gradient).max(0.0),
+    ))
+}
+
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Basis functions a `NoiseField` layers into octaves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseKind {
+    Value,
+    Perlin,
+    Simplex,
+    Worley,
+}
+
+impl NoiseKind {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "value" => Ok(Self::Value),
+            "perlin" => Ok(Self::Perlin),
+            "simplex" => Ok(Self::Simplex),
+            "worley" => Ok(Self::Worley),
+            other => Err(format!("Unknown noise kind: {other}")),
+        }
+    }
+
+    /// One octave at `(x, y)`, roughly in -1..1 with lattice features one unit apart.
+    pub fn sample(self, seed: u32, x: f32, y: f32) -> f32 {
+        match self {
+            Self::Value => value_noise(seed, x, y),
+            Self::Perlin => perlin_noise(seed, x, y),
+            Self::Simplex => simplex_noise(seed, x, y),
+            Self::Worley => worley_noise(seed, x, y),
+        }
+    }
+}
+
+/// A noise field over a ground tile, built from a `NoiseConfig`.
+#[derive(Debug, Clone)]
+pub struct NoiseField {
+    pub kind: NoiseKind,
+    pub seed: u32,
+    /// Seed of the field inside this tile; `seed` alone is used along the tile's edges.
+    pub tile_seed: u32,
+    pub frequency: f32,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub warp: f32,
+    /// How strongly the field drives its target, from 0 (white noise as before) to 1.
+    pub amount: f32,
+}
+
+impl NoiseField {
+    /// The field `config` describes for the tile drawn from `seed`. Without a config the field
+    /// has an `amount` of 0, so the tile keeps its uniform randomness.
+    pub fn from_config(config: Option<&NoiseConfig>, seed: u64) -> Result<Self, String> {
+        let Some(config) = config else {
+            return Ok(Self {
+                kind: NoiseKind::Value,
+                seed: 0,
+                tile_seed: 0,
+                frequency: 1.0,
+                octaves: 1,
+                lacunarity: 2.0,
+                gain: 0.5,
+                warp: 0.0,
+                amount: 0.0,
+            });
+        };
+        if config.octaves == 0 {
+            return Err("Noise octaves must be at least 1".to_string());
+        }
+        let tile_seed = (seed ^ (seed >> 32)) as u32;
+        Ok(Self {
+            kind: NoiseKind::from_name(&config.kind)?,
+            seed: config.seed,
+            tile_seed: config.seed ^ tile_seed.wrapping_mul(0x9e37_79b9).rotate_left(16),
+            frequency: config.frequency,
+            octaves: config.octaves,
+            lacunarity: config.lacunarity,
+            gain: config.gain,
+            warp: config.warp,
+            amount: config.amount.clamp(0.0, 1.0),
+        })
+    }
+
+    /// Samples the field at ground coordinates `(u, v)` in 0..1 and returns a value in 0..1.
+    /// Near the tile's edges the field shared by every tile takes over from the tile's own, so
+    /// neighbors meet without a seam while each tile varies inside.
+    pub fn sample(&self, u: f32, v: f32) -> f32 {
+        if self.amount == 0.0 {
+            return 0.5;
+        }
+        let (u, v) = (u.rem_euclid(1.0), v.rem_euclid(1.0));
+        let edge = u.min(1.0 - u).min(v).min(1.0 - v);
+        let inside = smoothstep(0.0, 0.2, edge);
+        let shared = self.wrapped(self.seed, u, v);
+        let value = if inside > 0.0 {
+            shared + (self.wrapped(self.tile_seed, u, v) - shared) * inside
+        } else {
+            shared
+        };
+        (value * 0.5 + 0.5).clamp(0.0, 1.0)
+    }
+
+    /// The field for `seed`, with four copies shifted by a whole tile blended so it wraps at
+    /// the tile's edges.
+    fn wrapped(&self, seed: u32, u: f32, v: f32) -> f32 {
+        let weights = [(1.0 - u) * (1.0 - v), u * (1.0 - v), (1.0 - u) * v, u * v];
+        let shifts = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)];
+        let mut sum = 0.0;
+        for (weight, (du, dv)) in weights.iter().zip(shifts) {
+            sum += weight * self.raw(seed, u - du, v - dv);
+        }
+        // Blending flattens the middle of the tile; rescale to the unblended contrast.
+        let spread = weights.iter().map(|weight| weight * weight).sum::<f32>().sqrt();
+        sum / spread
+    }
+
+    fn raw(&self, seed: u32, u: f32, v: f32) -> f32 {
+        let (x, y) = (u * self.frequency, v * self.frequency);
+        let (x, y) = domain_warp(self.kind, seed, x, y, self.warp, self.octaves);
+        fbm(self.kind, seed, x, y, self.octaves, self.lacunarity, self.gain)
+    }
+}
+
+/// Fractal Brownian motion: `octaves` layers of `kind`, each `lacunarity` times finer and
+/// `gain` times weaker than the last, normalized back to about -1..1.
+pub fn fbm(
+    kind: NoiseKind,
+    seed: u32,
+    x: f32,
+    y: f32,
+    octaves: u32,
+    lacunarity: f32,
+    gain: f32,
+) -> f32 {
+    let mut sum = 0.0;
+    let mut total = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    for octave in 0..octaves.max(1) {
+        sum += amplitude * kind.sample(seed.wrapping_add(octave), x * frequency, y * frequency);
+        total += amplitude;
+        amplitude *= gain;
+        frequency *= lacunarity;
+    }
+    sum / total
+}
+
+/// Pushes `(x, y)` along two more noise fields, `amount` lattice units at most, so features
+/// swirl instead of sitting on the lattice.
+pub fn domain_warp(
+    kind: NoiseKind,
+    seed: u32,
+    x: f32,
+    y: f32,
+    amount: f32,
+    octaves: u32,
+) -> (f32, f32) {
+    if amount == 0.0 {
+        return (x, y);
+    }
+    let warp_x = fbm(kind, seed ^ 0x5bd1_e995, x, y, octaves, 2.0, 0.5);
+    let warp_y = fbm(kind, seed ^ 0x27d4_eb2f, x + 5.2, y + 1.3, octaves, 2.0, 0.5);
+    (x + warp_x * amount, y + warp_y * amount)
+}
+
+/// Smoothly interpolated random values at the integer lattice points.
+pub fn value_noise(seed: u32, x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (fade(x - x0), fade(y - y0));
+    let (ix, iy) = (x0 as i32, y0 as i32);
+    let corner = |dx: i32, dy: i32| hash_unit(seed, ix + dx, iy + dy) * 2.0 - 1.0;
+    lerp(
+        lerp(corner(0, 0), corner(1, 0), tx),
+        lerp(corner(0, 1), corner(1, 1), tx),
+        ty,
+    )
+}
+
+/// Classic gradient noise with random unit gradients at the integer lattice points.
+pub fn perlin_noise(seed: u32, x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (ix, iy) = (x0 as i32, y0 as i32);
+    let corner = |dx: i32, dy: i32| {
+        gradient(seed, ix + dx, iy + dy, fx - dx as f32, fy - dy as f32)
+    };
+    let (tx, ty) = (fade(fx), fade(fy));
+    let value = lerp(
+        lerp(corner(0, 0), corner(1, 0), tx),
+        lerp(corner(0, 1), corner(1, 1), tx),
+        ty,
+    );
+    value * std::f32::consts::SQRT_2
+}
+
+/// Gradient noise summed over the corners of a triangular lattice, without the axis-aligned
+/// streaks of Perlin noise.
+pub fn simplex_noise(seed: u32, x: f32, y: f32) -> f32 {
+    const SKEW: f32 = 0.366_025_42; // (sqrt(3) - 1) / 2
+    const UNSKEW: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+    let skew = (x + y) * SKEW;
+    let (i, j) = ((x + skew).floor(), (y + skew).floor());
+    let unskew = (i + j) * UNSKEW;
+    let (x0, y0) = (x - (i - unskew), y - (j - unskew));
+    let (step_i, step_j) = if x0 > y0 { (1, 0) } else { (0, 1) };
+    let corners = [
+        (0, 0, x0, y0),
+        (
+            step_i,
+            step_j,
+            x0 - step_i as f32 + UNSKEW,
+            y0 - step_j as f32 + UNSKEW,
+        ),
+        (1, 1, x0 - 1.0 + 2.0 * UNSKEW, y0 - 1.0 + 2.0 * UNSKEW),
+    ];
+    let (i, j) = (i as i32, j as i32);
+    let mut sum = 0.0;
+    for (di, dj, dx, dy) in corners {
+        let falloff = 0.5 - dx * dx - dy * dy;
+        if falloff > 0.0 {
+            sum += falloff.powi(4) * gradient(seed, i + di, j + dj, dx, dy);
+        }
+    }
+    (sum * 70.0).clamp(-1.0, 1.0)
+}
+
+/// Cellular noise: high next to one random feature point per lattice cell, falling off with
+/// the distance to the nearest one.
+pub fn worley_noise(seed: u32, x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (ix, iy) = (x0 as i32, y0 as i32);
+    let mut nearest = f32::MAX;
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let (cx, cy) = (ix + dx, iy + dy);
+            let px = cx as f32 + hash_unit(seed, cx, cy);
+            let py = cy as f32 + hash_unit(seed ^ 0x68e3_1da4, cx, cy);
+            nearest = nearest.min((px - x).hypot(py - y));
+        }
+    }
+    1.0 - 2.0 * nearest.min(1.0)
+}
+
+fn gradient(seed: u32, x: i32, y: i32, dx: f32, dy: f32) -> f32 {
+    let angle = hash_unit(seed ^ 0x1b87_3593, x, y) * std::f32::consts::TAU;
+    angle.cos() * dx + angle.sin() * dy
+}
+
+fn hash_unit(seed: u32, x: i32, y: i32) -> f32 {
+    let mut hash = seed
+        ^ (x as u32).wrapping_mul(0x27d4_eb2d)
+        ^ (y as u32).wrapping_mul(0x1656_67b1);
+    hash = (hash ^ (hash >> 15)).wrapping_mul(0x85eb_ca6b);
+    hash = (hash ^ (hash >> 13)).wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+    hash as f32 / u32::MAX as f32
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Where pixel `(x, y)` sits on the tile's ground diamond: `u` runs from the upper left edge
+/// to the lower right one and `v` from the upper right edge to the lower left one.
+pub fn tile_ground_uv(x: u32, y: u32, sprite_width: u32, sprite_height: u32) -> (f32, f32) {
+    let width = sprite_width.saturating_sub(1).max(1) as f32;
+    let half = width / 2.0;
+    let top_y = sprite_height.saturating_sub(1) as f32 - half;
+    let across = x as f32 / width - 0.5;
+    let down = (y as f32 - top_y) / half;
+    (down + across, down - across)
+}
+
 fn put_pixel_safe(img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, x: i32, y: i32, color: Rgba<u8>) {
     if x >= 0 && y >= 0 {
         let (x, y) = (x as u32, y as u32);
@@ -108,3 +481,74 @@ fn put_pixel_safe(img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, x: i32, y: i32, colo
 fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a + (b - a) * t
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_edge_overrides_replace_config_values() {
+        let config = (Some(0.1), Some(0.2));
+        let settings = transition_edge_settings("ice", config, Some((Some(1.6), None)));
+        assert_eq!(settings, Ok((1.0, 0.2)));
+        assert_eq!(
+            transition_edge_settings("ice", config, None),
+            Ok((0.1, 0.2))
+        );
+        assert_eq!(
+            transition_edge_settings("snow", (None, Some(0.2)), None),
+            Err("Missing required config field: snow_edge_cutoff".to_string())
+        );
+    }
+
+    #[test]
+    fn downsample_resolves_coverage_and_majority() {
+        let red = Rgba([200, 0, 0, 255]);
+        let blue = Rgba([0, 0, 200, 255]);
+        let clear = Rgba([0, 0, 0, 0]);
+        // One 2x2 block: two red, one blue, one empty sample.
+        let image = ImageBuffer::from_fn(2, 2, |x, y| match (x, y) {
+            (0, 0) | (1, 0) => red,
+            (0, 1) => blue,
+            _ => clear,
+        });
+        let coverage = downsample(&image, 2, SampleResolve::Coverage);
+        assert_eq!(*coverage.get_pixel(0, 0), Rgba([133, 0, 67, 191]));
+        let majority = downsample(&image, 2, SampleResolve::Majority);
+        assert_eq!(*majority.get_pixel(0, 0), red);
+    }
+
+    #[test]
+    fn noise_fields_wrap_at_tile_edges_and_vary_inside() {
+        for kind in ["value", "perlin", "simplex", "worley"] {
+            let config = NoiseConfig {
+                kind: kind.to_string(),
+                seed: 9,
+                frequency: 3.0,
+                octaves: 3,
+                lacunarity: 2.0,
+                gain: 0.5,
+                warp: 0.4,
+                amount: 1.0,
+            };
+            let field = NoiseField::from_config(Some(&config), 1).unwrap();
+            let other = NoiseField::from_config(Some(&config), 2).unwrap();
+            for step in 0..=10 {
+                let t = step as f32 / 10.0;
+                let across = (field.sample(0.0, t), other.sample(0.9999, t));
+                let down = (field.sample(t, 0.0), other.sample(t, 0.9999));
+                for (a, b) in [across, down] {
+                    assert!((a - b).abs() < 0.01, "{kind} seam at {t}: {a} vs {b}");
+                    assert!((0.0..=1.0).contains(&a));
+                }
+            }
+            let differs = (1..10).any(|step| {
+                let t = step as f32 / 10.0;
+                (field.sample(0.5, t) - other.sample(0.5, t)).abs() > 0.05
+            });
+            assert!(differs, "{kind} tiles share one pattern");
+        }
+        let off = NoiseField::from_config(None, 1).unwrap();
+        assert_eq!(off.amount, 0.0);
+    }
+}
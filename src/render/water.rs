@@ -1,13 +1,16 @@
 use image::{ImageBuffer, Rgba};
 
 use crate::config::{require_field, TileConfig};
-use crate::render::util::{draw_isometric_ground, parse_hex_color};
+use crate::render::util::{
+    draw_isometric_ground, mix_color, parse_hex_color, tile_ground_uv, NoiseField,
+};
 use spriteforge_assets::edge_weight_for_mask;
 
 pub fn render_water_tile(
     sprite_width: u32,
     sprite_height: u32,
     bg: Rgba<u8>,
+    seed: u64,
     config: &TileConfig,
 ) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, String> {
     if config.name != "water" {
@@ -17,13 +20,43 @@ pub fn render_water_tile(
     let water_base = require_field(config.water_base.clone(), "water_base")?;
     let water = parse_hex_color(&water_base)?;
     draw_isometric_ground(&mut img, sprite_width, sprite_height, water);
+    apply_water_tone(&mut img, seed, config)?;
     Ok(img)
 }
 
+/// Lightens and darkens the water by `water_tone_noise`, up to `amount` of the way to white
+/// or black, so open water shows deeper and shallower patches.
+fn apply_water_tone(
+    img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
+    seed: u64,
+    config: &TileConfig,
+) -> Result<(), String> {
+    let noise = NoiseField::from_config(config.water_tone_noise.as_ref(), seed)?;
+    if noise.amount == 0.0 {
+        return Ok(());
+    }
+    let (width, height) = img.dimensions();
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        if pixel.0[3] == 0 {
+            continue;
+        }
+        let (u, v) = tile_ground_uv(x, y, width, height);
+        let tone = (noise.sample(u, v) - 0.5) * 2.0 * noise.amount;
+        let target = if tone > 0.0 {
+            Rgba([255, 255, 255, pixel[3]])
+        } else {
+            Rgba([0, 0, 0, pixel[3]])
+        };
+        *pixel = mix_color(*pixel, target, tone.abs());
+    }
+    Ok(())
+}
+
 pub fn render_water_transition_tile(
     sprite_width: u32,
     sprite_height: u32,
     bg: Rgba<u8>,
+    seed: u64,
     config: &TileConfig,
     transition_mask: u8,
     overrides: Option<&crate::config::TransitionOverrides>,
@@ -44,6 +77,7 @@ pub fn render_water_transition_tile(
 
     let mut img = ImageBuffer::from_pixel(sprite_width, sprite_height, bg);
     draw_isometric_ground(&mut img, sprite_width, sprite_height, water);
+    apply_water_tone(&mut img, seed, config)?;
     let gradient = 0.0;
     let width = img.width().max(1) as f32;
     for (x, y, pixel) in img.enumerate_pixels_mut() {
@@ -0,0 +1,187 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::tree::{cross, Vec3};
+
+#[derive(Debug, Clone, Default)]
+pub struct RockModel {
+    pub vertices: Vec<Vec3>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RockSettings {
+    pub radius: f32,
+    pub squash: f32,
+    pub roughness: f32,
+    pub rings: u32,
+    pub segments: u32,
+}
+
+impl Default for RockSettings {
+    fn default() -> Self {
+        Self {
+            radius: 1.0,
+            squash: 0.6,
+            roughness: 0.25,
+            rings: 5,
+            segments: 8,
+        }
+    }
+}
+
+impl RockModel {
+    pub fn face_normal(&self, triangle: [usize; 3]) -> Vec3 {
+        let a = self.vertices[triangle[0]];
+        let b = self.vertices[triangle[1]];
+        let c = self.vertices[triangle[2]];
+        cross(b - a, c - a).normalized()
+    }
+}
+
+/// Builds a low-poly rock from a jittered lat-long sphere whose radius is displaced by a
+/// few random lobes. The bottom is flattened so the rock rests on z = 0.
+pub fn generate_rock(seed: u64, settings: &RockSettings) -> RockModel {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let rings = settings.rings.max(2);
+    let segments = settings.segments.max(3);
+    let stretch = Vec3::new(
+        rng.gen_range(0.8..1.2),
+        rng.gen_range(0.8..1.2),
+        settings.squash.max(0.1),
+    );
+    let lobes: Vec<(Vec3, f32, f32)> = (0..4)
+        .map(|_| {
+            let direction = Vec3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            )
+            .normalized();
+            (
+                direction,
+                rng.gen_range(1.5..3.5),
+                rng.gen_range(0.0..std::f32::consts::TAU),
+            )
+        })
+        .collect();
+
+    let mut model = RockModel::default();
+    let top = push_vertex(
+        &mut model,
+        Vec3::new(0.0, 0.0, 1.0),
+        settings,
+        stretch,
+        &lobes,
+    );
+    let mut ring_starts = Vec::with_capacity(rings as usize - 1);
+    let twist = rng.gen_range(0.0..std::f32::consts::TAU);
+    for ring in 1..rings {
+        ring_starts.push(model.vertices.len());
+        let polar = ring as f32 / rings as f32 * std::f32::consts::PI;
+        for segment in 0..segments {
+            let jitter = rng.gen_range(-0.3..0.3);
+            let azimuth =
+                (segment as f32 + jitter) / segments as f32 * std::f32::consts::TAU + twist;
+            let polar = polar + rng.gen_range(-0.15..0.15);
+            let direction = Vec3::new(
+                polar.sin() * azimuth.cos(),
+                polar.sin() * azimuth.sin(),
+                polar.cos(),
+            );
+            push_vertex(&mut model, direction, settings, stretch, &lobes);
+        }
+    }
+    let bottom = push_vertex(
+        &mut model,
+        Vec3::new(0.0, 0.0, -1.0),
+        settings,
+        stretch,
+        &lobes,
+    );
+
+    let segments = segments as usize;
+    for segment in 0..segments {
+        let next = (segment + 1) % segments;
+        let first = ring_starts[0];
+        model.triangles.push([top, first + segment, first + next]);
+        let last = ring_starts[ring_starts.len() - 1];
+        model.triangles.push([bottom, last + next, last + segment]);
+    }
+    for pair in ring_starts.windows(2) {
+        let (upper, lower) = (pair[0], pair[1]);
+        for segment in 0..segments {
+            let next = (segment + 1) % segments;
+            model
+                .triangles
+                .push([upper + segment, lower + segment, upper + next]);
+            model
+                .triangles
+                .push([upper + next, lower + segment, lower + next]);
+        }
+    }
+
+    // Sink the rock a little and flatten everything below ground.
+    let min_z = model
+        .vertices
+        .iter()
+        .map(|vertex| vertex.z)
+        .fold(f32::MAX, f32::min);
+    let sink = min_z * 0.6;
+    for vertex in &mut model.vertices {
+        vertex.z = (vertex.z - sink).max(0.0);
+    }
+    model
+}
+
+fn push_vertex(
+    model: &mut RockModel,
+    direction: Vec3,
+    settings: &RockSettings,
+    stretch: Vec3,
+    lobes: &[(Vec3, f32, f32)],
+) -> usize {
+    let mut displacement = 0.0;
+    for (axis, frequency, phase) in lobes {
+        let along = direction.x * axis.x + direction.y * axis.y + direction.z * axis.z;
+        displacement += (along * frequency + phase).sin();
+    }
+    let scale =
+        settings.radius * (1.0 + settings.roughness * displacement / lobes.len().max(1) as f32);
+    model.vertices.push(Vec3::new(
+        direction.x * stretch.x * scale,
+        direction.y * stretch.y * scale,
+        direction.z * stretch.z * scale,
+    ));
+    model.vertices.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rock_faces_point_outward() {
+        let model = generate_rock(11, &RockSettings::default());
+        let center = model
+            .vertices
+            .iter()
+            .fold(Vec3::default(), |sum, vertex| sum + *vertex)
+            * (1.0 / model.vertices.len() as f32);
+        let outward = model
+            .triangles
+            .iter()
+            .filter(|triangle| {
+                let normal = model.face_normal(**triangle);
+                let centroid = (model.vertices[triangle[0]]
+                    + model.vertices[triangle[1]]
+                    + model.vertices[triangle[2]])
+                    * (1.0 / 3.0);
+                let offset = centroid - center;
+                normal.x * offset.x + normal.y * offset.y + normal.z * offset.z >= 0.0
+            })
+            .count();
+        assert!(outward * 10 >= model.triangles.len() * 9);
+        assert!(model.vertices.iter().all(|vertex| vertex.z >= 0.0));
+    }
+}
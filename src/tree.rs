@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use rand::seq::SliceRandom;
 
+/// Phase delay in radians per segment depth level of the wind sway.
+const SWAY_LAG: f32 = 0.12;
+
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct Vec3 {
     pub x: f32,
@@ -58,6 +63,8 @@ pub struct TreeSegment {
     pub radius: f32,
     pub normal: Vec3,
     pub depth: u32,
+    /// Segment this one grows from; `None` for the trunk base and the roots.
+    pub parent: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -66,6 +73,8 @@ pub struct TreeLeafStem {
     pub end: Vec3,
     pub radius: f32,
     pub normal: Vec3,
+    /// Branch segment the stem hangs from.
+    pub segment: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -80,6 +89,72 @@ pub struct TreeModel {
     pub segments: Vec<TreeSegment>,
     pub leaf_stems: Vec<TreeLeafStem>,
     pub leaves: Vec<TreeLeaf>,
+    /// Space colonization steps until the crown consumed its last attraction point; later
+    /// steps only crowd existing branches.
+    pub iterations: u32,
+}
+
+/// Envelope the attraction points are scattered in, fitted to `crown_radius` by
+/// `crown_height` above the trunk.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrownShape {
+    Cylinder,
+    Sphere,
+    Ellipsoid,
+    /// Widest at the bottom and tapering to a point, for conifers.
+    Cone,
+    /// Wide, flat-topped canopy at the top of the crown, like an acacia.
+    Umbrella,
+    /// Thin drooping shell of fronds radiating from the crown top.
+    Palm,
+    /// Hollow outer shell of an ellipsoid; pair with negative tropism for hanging branches.
+    Weeping,
+}
+
+impl CrownShape {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "cylinder" => Ok(Self::Cylinder),
+            "sphere" => Ok(Self::Sphere),
+            "ellipsoid" => Ok(Self::Ellipsoid),
+            "cone" => Ok(Self::Cone),
+            "umbrella" => Ok(Self::Umbrella),
+            "palm" => Ok(Self::Palm),
+            "weeping" => Ok(Self::Weeping),
+            other => Err(format!("Unknown crown shape: {other}")),
+        }
+    }
+
+    /// Whether a point `radial` (0 at the axis, 1 at `crown_radius`) out and `t` (0 at the
+    /// crown base, 1 at its top) up lies inside the envelope. Every shape fits inside the
+    /// cylinder, which is what callers sample from.
+    fn contains(self, radial: f32, t: f32, radius: f32, height: f32) -> bool {
+        let centered = (t - 0.5) / 0.5;
+        match self {
+            Self::Cylinder => true,
+            Self::Sphere => {
+                // A true sphere as large as the crown box allows.
+                let sphere = radius.min(height * 0.5).max(f32::EPSILON);
+                let dx = radial * radius / sphere;
+                let dz = (t - 0.5) * height / sphere;
+                dx * dx + dz * dz <= 1.0
+            }
+            Self::Ellipsoid => radial * radial + centered * centered <= 1.0,
+            Self::Cone => radial <= 1.0 - t,
+            Self::Umbrella => {
+                let dome = (t - 0.8) / 0.2;
+                t >= 0.6 && radial * radial + dome * dome <= 1.0
+            }
+            Self::Palm => {
+                let droop = 1.0 - 0.6 * radial * radial;
+                (t - droop).abs() < 0.1
+            }
+            Self::Weeping => {
+                let shell = radial * radial + centered * centered;
+                (0.45..=1.0).contains(&shell)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -96,6 +171,12 @@ pub struct TreeSettings {
     pub leaf_size: f32,
     pub max_leaves: u32,
     pub leaf_normal_bias: f32,
+    pub crown_shape: CrownShape,
+    /// Bias added to every growth step along +z: positive bends branches upward, negative
+    /// makes them droop.
+    pub tropism: f32,
+    /// Trunk tilt from vertical in radians, toward a direction picked from the seed.
+    pub lean: f32,
 }
 
 impl Default for TreeSettings {
@@ -113,6 +194,27 @@ impl Default for TreeSettings {
             leaf_size: 0.55,
             max_leaves: 120,
             leaf_normal_bias: 0.35,
+            crown_shape: CrownShape::Cylinder,
+            tropism: 0.0,
+            lean: 0.0,
+        }
+    }
+}
+
+impl TreeSettings {
+    /// Settings for the same tree at `fraction` of its growth, where 1.0 is mature and the
+    /// mature tree grew for `mature_iterations` steps. Younger trees stop colonizing early with
+    /// a shorter, thinner trunk and fewer leaves; past 1.0 the trunk thickens and the crown
+    /// thins out.
+    pub fn at_growth(&self, fraction: f32, mature_iterations: u32) -> Self {
+        let iterations = (mature_iterations as f32 * fraction.min(1.0)).ceil() as u32;
+        let leaves = if fraction <= 1.0 { fraction } else { 1.0 / fraction };
+        Self {
+            trunk_height: self.trunk_height * fraction.min(1.0),
+            max_iterations: iterations.clamp(1, self.max_iterations),
+            base_radius: self.base_radius * fraction.sqrt(),
+            max_leaves: (self.max_leaves as f32 * leaves).round() as u32,
+            ..self.clone()
         }
     }
 }
@@ -127,11 +229,24 @@ struct Node {
 }
 
 pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
+    grow_tree(seed, settings, Lookup::Grid)
+}
+
+/// How growth finds the node nearest an attraction point. Both give identical trees; brute
+/// force stays around to check that and to benchmark against.
+#[derive(Debug, Clone, Copy)]
+enum Lookup {
+    Grid,
+    #[cfg(test)]
+    BruteForce,
+}
+
+fn grow_tree(seed: u64, settings: &TreeSettings, lookup: Lookup) -> TreeModel {
     let mut rng = StdRng::seed_from_u64(seed);
     let mut attraction_points = Vec::with_capacity(settings.attraction_points as usize);
 
     for _ in 0..settings.attraction_points {
-        let mut point = Vec3::default();
+        let mut point = None;
         for _ in 0..30 {
             let x = rng.gen_range(-1.0..1.0) * settings.crown_radius;
             let y = rng.gen_range(-1.0..1.0) * settings.crown_radius;
@@ -139,15 +254,50 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
                 continue;
             }
             let z = rng.gen_range(0.0..settings.crown_height);
-            point = Vec3::new(x, y, z + settings.trunk_height);
+            let radial = (x * x + y * y).sqrt() / settings.crown_radius.max(f32::EPSILON);
+            let t = z / settings.crown_height.max(f32::EPSILON);
+            if !settings.crown_shape.contains(
+                radial,
+                t,
+                settings.crown_radius,
+                settings.crown_height,
+            ) {
+                continue;
+            }
+            point = Some(Vec3::new(x, y, z + settings.trunk_height));
             break;
         }
-        attraction_points.push(point);
+        // Thin envelopes occasionally reject every try; skip the point rather than bunch it
+        // at the root.
+        if let Some(point) = point {
+            attraction_points.push(point);
+        }
+    }
+
+    // The trunk leans with a constant slope, and the crown moves with the trunk top.
+    let lean = if settings.lean != 0.0 {
+        let heading = rng.gen_range(0.0..std::f32::consts::TAU);
+        let slope = settings.lean.tan();
+        Vec3::new(heading.cos() * slope, heading.sin() * slope, 0.0)
+    } else {
+        Vec3::default()
+    };
+    for point in attraction_points.iter_mut() {
+        *point = *point + lean * settings.trunk_height;
     }
     let initial_attraction_points = attraction_points.clone();
 
     let mut nodes = Vec::new();
     let mut segments = Vec::new();
+    let mut node_segments = vec![None];
+    // Points farther than this from every node neither grow nor die, so lookups can stop there.
+    let reach = settings.influence_distance.max(settings.kill_distance);
+    let mut grid = NodeGrid::new(reach.max(settings.segment_length));
+    let nearest = |grid: &NodeGrid, nodes: &[Node], point: Vec3, radius: Option<f32>| match lookup {
+        Lookup::Grid => grid.nearest(nodes, point, radius),
+        #[cfg(test)]
+        Lookup::BruteForce => nearest_node_brute_force(nodes, point),
+    };
     let trunk_step = settings.segment_length.max(0.05);
     let trunk_steps = (settings.trunk_height / trunk_step).ceil().max(1.0) as u32;
     let mut prev_index = 0usize;
@@ -157,27 +307,32 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
         parent: None,
         depth: 0,
     });
+    grid.insert(0, nodes[0].position);
     for step in 1..=trunk_steps {
         let z = (step as f32 * trunk_step).min(settings.trunk_height);
         let depth = nodes[prev_index].depth + 1;
         nodes.push(Node {
-            position: Vec3::new(0.0, 0.0, z),
+            position: Vec3::new(0.0, 0.0, z) + lean * z,
             children: if step == trunk_steps { 0 } else { 1 },
             parent: Some(prev_index),
             depth,
         });
         let next_index = nodes.len() - 1;
+        grid.insert(next_index, nodes[next_index].position);
         segments.push(TreeSegment {
             start: nodes[prev_index].position,
             end: nodes[next_index].position,
             radius: settings.base_radius,
             normal: Vec3::default(),
             depth,
+            parent: node_segments[prev_index],
         });
+        node_segments.push(Some(segments.len() - 1));
         prev_index = next_index;
     }
 
     let mut iter = 0;
+    let mut grown = 0;
     while !attraction_points.is_empty() && iter < settings.max_iterations {
         iter += 1;
 
@@ -185,17 +340,12 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
         let mut direction_counts = vec![0u32; nodes.len()];
         let mut remaining_points = Vec::with_capacity(attraction_points.len());
 
+        let point_count = attraction_points.len();
         for point in attraction_points.into_iter() {
-            let mut closest = None;
-            let mut closest_dist = f32::MAX;
-            for (idx, node) in nodes.iter().enumerate() {
-                let delta = point - node.position;
-                let dist = delta.length();
-                if dist < closest_dist {
-                    closest_dist = dist;
-                    closest = Some((idx, delta));
-                }
-            }
+            let (closest, closest_dist) = match nearest(&grid, &nodes, point, Some(reach)) {
+                Some((idx, dist)) => (Some((idx, point - nodes[idx].position)), dist),
+                None => (None, f32::MAX),
+            };
 
             if closest_dist <= settings.kill_distance {
                 continue;
@@ -209,6 +359,9 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
             remaining_points.push(point);
         }
 
+        if remaining_points.len() < point_count {
+            grown = iter;
+        }
         attraction_points = remaining_points;
 
         let mut new_nodes = Vec::new();
@@ -217,6 +370,7 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
                 continue;
             }
             let direction = direction_sums[idx] * (1.0 / (*count as f32));
+            let direction = direction.normalized() + Vec3::new(0.0, 0.0, settings.tropism);
             let new_pos = nodes[idx].position + direction.normalized() * settings.segment_length;
             new_nodes.push((idx, new_pos));
         }
@@ -234,13 +388,16 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
                 parent: Some(parent_idx),
                 depth,
             });
+            grid.insert(nodes.len() - 1, position);
             segments.push(TreeSegment {
                 start: nodes[parent_idx].position,
                 end: position,
                 radius: 0.0,
                 normal: Vec3::default(),
                 depth,
+                parent: node_segments[parent_idx],
             });
+            node_segments.push(Some(segments.len() - 1));
         }
     }
 
@@ -283,13 +440,14 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
             .collect();
         let eligible_attraction_points: Vec<Vec3> = initial_attraction_points
             .iter()
-            .map(|point| nearest_node(&nodes, *point))
+            .filter_map(|point| nearest(&grid, &nodes, *point, None))
+            .map(|(idx, _)| nodes[idx].position)
             .filter(|pos| pos.z > min_leaf_z)
             .collect();
 
         if target_leaf_count >= eligible_attraction_points.len() {
             for position in &eligible_attraction_points {
-                let (stem_start, stem_end, normal) = leaf_stem_for_point(
+                let (stem_start, stem_end, normal, segment) = leaf_stem_for_point(
                     *position,
                     tree_center,
                     &segments,
@@ -302,6 +460,7 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
                     end: stem_end,
                     radius: settings.leaf_size * 0.15,
                     normal,
+                    segment,
                 });
                 leaves.push(TreeLeaf {
                     position: stem_end,
@@ -315,7 +474,7 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
                 }
                 let idx = rng.gen_range(0..eligible_nodes.len());
                 let position = eligible_nodes[idx];
-                let (stem_start, stem_end, normal) = leaf_stem_for_point(
+                let (stem_start, stem_end, normal, segment) = leaf_stem_for_point(
                     position,
                     tree_center,
                     &segments,
@@ -328,6 +487,7 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
                     end: stem_end,
                     radius: settings.leaf_size * 0.15,
                     normal,
+                    segment,
                 });
                 leaves.push(TreeLeaf {
                     position: stem_end,
@@ -340,7 +500,7 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
             indices.shuffle(&mut rng);
             for idx in indices.into_iter().take(target_leaf_count) {
                 let position = eligible_attraction_points[idx];
-                let (stem_start, stem_end, normal) = leaf_stem_for_point(
+                let (stem_start, stem_end, normal, segment) = leaf_stem_for_point(
                     position,
                     tree_center,
                     &segments,
@@ -353,6 +513,7 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
                     end: stem_end,
                     radius: settings.leaf_size * 0.15,
                     normal,
+                    segment,
                 });
                 leaves.push(TreeLeaf {
                     position: stem_end,
@@ -367,6 +528,282 @@ pub fn generate_tree(seed: u64, settings: &TreeSettings) -> TreeModel {
         segments,
         leaf_stems,
         leaves,
+        iterations: grown,
+    }
+}
+
+/// Poses `model` at `phase` (in turns) of a wind cycle blowing toward screen right. Every
+/// segment turns about the joint with its parent by an angle that grows with its depth and lags
+/// a little per level, so gusts ripple out from the trunk to the twigs. Roots stay put.
+pub fn sway_tree(model: &TreeModel, amplitude: f32, phase: f32) -> TreeModel {
+    let wind = Vec3::new(1.0, -1.0, 0.0).normalized();
+    let axis = cross(Vec3::new(0.0, 0.0, 1.0), wind);
+    let max_depth = model
+        .segments
+        .iter()
+        .map(|segment| segment.depth)
+        .max()
+        .unwrap_or(1)
+        .max(1) as f32;
+    let wave = phase * std::f32::consts::TAU;
+
+    // Parents are pushed before their children, so one pass sees every parent posed.
+    let mut angles = Vec::with_capacity(model.segments.len());
+    let mut segments: Vec<TreeSegment> = Vec::with_capacity(model.segments.len());
+    for segment in &model.segments {
+        let reach = segment.depth as f32 / max_depth;
+        let angle = amplitude * reach.powf(1.5) * (wave - segment.depth as f32 * SWAY_LAG).sin();
+        let start = match segment.parent {
+            Some(parent) => segments[parent].end,
+            None => segment.start,
+        };
+        let end = start + rotate_about(segment.end - segment.start, axis, angle);
+        segments.push(TreeSegment {
+            start,
+            end,
+            normal: rotate_about(segment.normal, axis, angle),
+            ..segment.clone()
+        });
+        angles.push(angle);
+    }
+
+    let mut leaf_stems = Vec::with_capacity(model.leaf_stems.len());
+    let mut leaves = Vec::with_capacity(model.leaves.len());
+    for (stem, leaf) in model.leaf_stems.iter().zip(&model.leaves) {
+        let (start, angle) = match stem.segment {
+            Some(index) => (segments[index].end, angles[index]),
+            None => (stem.start, 0.0),
+        };
+        let end = start + rotate_about(stem.end - stem.start, axis, angle);
+        let normal = rotate_about(stem.normal, axis, angle);
+        leaf_stems.push(TreeLeafStem {
+            start,
+            end,
+            normal,
+            ..stem.clone()
+        });
+        leaves.push(TreeLeaf {
+            position: end + (leaf.position - stem.end),
+            normal: rotate_about(leaf.normal, axis, angle),
+            ..leaf.clone()
+        });
+    }
+
+    TreeModel {
+        segments,
+        leaf_stems,
+        leaves,
+        iterations: model.iterations,
+    }
+}
+
+/// Turns `model` `quarter_turns` times 90 degrees counterclockwise about the vertical axis
+/// through its base, which is how it looks once the camera has orbited as far clockwise.
+/// Normals turn with it, so each view's mask stays lit from the same screen direction.
+pub fn rotate_tree(model: &TreeModel, quarter_turns: u32) -> TreeModel {
+    let turn = |v: Vec3| match quarter_turns % 4 {
+        0 => v,
+        1 => Vec3::new(-v.y, v.x, v.z),
+        2 => Vec3::new(-v.x, -v.y, v.z),
+        _ => Vec3::new(v.y, -v.x, v.z),
+    };
+    TreeModel {
+        segments: model
+            .segments
+            .iter()
+            .map(|segment| TreeSegment {
+                start: turn(segment.start),
+                end: turn(segment.end),
+                normal: turn(segment.normal),
+                ..segment.clone()
+            })
+            .collect(),
+        leaf_stems: model
+            .leaf_stems
+            .iter()
+            .map(|stem| TreeLeafStem {
+                start: turn(stem.start),
+                end: turn(stem.end),
+                normal: turn(stem.normal),
+                ..stem.clone()
+            })
+            .collect(),
+        leaves: model
+            .leaves
+            .iter()
+            .map(|leaf| TreeLeaf {
+                position: turn(leaf.position),
+                normal: turn(leaf.normal),
+                ..leaf.clone()
+            })
+            .collect(),
+        iterations: model.iterations,
+    }
+}
+
+/// Rodrigues rotation of `v` about the unit `axis`.
+fn rotate_about(v: Vec3, axis: Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    let along = axis.x * v.x + axis.y * v.y + axis.z * v.z;
+    v * cos + cross(axis, v) * sin + axis * (along * (1.0 - cos))
+}
+
+/// Something hung on or set into a tree's crown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttachmentKind {
+    Apple,
+    Orange,
+    /// Bunches around the top of the trunk, under the fronds of a palm.
+    Coconut,
+    Blossom,
+    /// Small fruit set on the outside of a leaf cluster, for bushes.
+    Berry,
+    /// Sits in a branch fork.
+    Nest,
+}
+
+impl AttachmentKind {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "apple" => Ok(Self::Apple),
+            "orange" => Ok(Self::Orange),
+            "coconut" => Ok(Self::Coconut),
+            "blossom" => Ok(Self::Blossom),
+            "berry" => Ok(Self::Berry),
+            "nest" => Ok(Self::Nest),
+            other => Err(format!("Unknown tree attachment: {other}")),
+        }
+    }
+
+    /// Whether the attachment hangs from a leaf rather than a branch, and so goes wherever the
+    /// leaves go.
+    pub fn on_leaf(self) -> bool {
+        matches!(self, Self::Apple | Self::Orange | Self::Blossom | Self::Berry)
+    }
+}
+
+/// An attachment anchored by index to a leaf or a branch segment's end, so the same placement
+/// follows any pose of the model it was placed on.
+#[derive(Debug, Clone)]
+pub struct TreeAttachment {
+    pub kind: AttachmentKind,
+    pub anchor: usize,
+    /// From the anchor point to the attachment's center.
+    pub offset: Vec3,
+    pub size: f32,
+}
+
+impl TreeAttachment {
+    /// Position and outward normal of the attachment on `model`.
+    pub fn pose(&self, model: &TreeModel) -> (Vec3, Vec3) {
+        match self.kind {
+            AttachmentKind::Nest => {
+                let fork = model.segments[self.anchor].end;
+                (fork + self.offset, Vec3::new(0.0, 0.0, 1.0))
+            }
+            AttachmentKind::Coconut => {
+                let out = Vec3::new(self.offset.x, self.offset.y, 0.0).normalized();
+                (model.segments[self.anchor].end + self.offset, out)
+            }
+            _ => {
+                let leaf = &model.leaves[self.anchor];
+                (leaf.position + self.offset, leaf.normal)
+            }
+        }
+    }
+}
+
+/// Picks up to `count` distinct anchors for `kind` on `model`: fruit hangs under random
+/// leaves, blossoms and berries sit on their faces, nests sit in random forks in the upper
+/// half of the branches, and coconuts bunch around the trunk just under the lowest leaves.
+pub fn place_attachments(
+    model: &TreeModel,
+    kind: AttachmentKind,
+    count: u32,
+    size: f32,
+    rng: &mut StdRng,
+) -> Vec<TreeAttachment> {
+    let up = Vec3::new(0.0, 0.0, 1.0);
+    let mut children = vec![Vec::new(); model.segments.len()];
+    for (index, segment) in model.segments.iter().enumerate() {
+        if let Some(parent) = segment.parent {
+            children[parent].push(index);
+        }
+    }
+    let attach = |anchor: usize, offset: Vec3| TreeAttachment {
+        kind,
+        anchor,
+        offset,
+        size,
+    };
+    match kind {
+        AttachmentKind::Nest => {
+            let top = model
+                .segments
+                .iter()
+                .map(|segment| segment.end.z)
+                .fold(0.0, f32::max);
+            let mut forks: Vec<usize> = (0..model.segments.len())
+                .filter(|&index| {
+                    children[index].len() >= 2 && model.segments[index].end.z > top * 0.5
+                })
+                .collect();
+            forks.shuffle(rng);
+            forks.truncate(count as usize);
+            forks
+                .into_iter()
+                .map(|fork| attach(fork, up * (size * 0.3)))
+                .collect()
+        }
+        AttachmentKind::Coconut => {
+            if model.segments.is_empty() {
+                return Vec::new();
+            }
+            // Follow the trunk up from the first segment while it has a single child, and
+            // hang the bunch from the last of it still under the leaves.
+            let lowest_leaf = model
+                .leaves
+                .iter()
+                .map(|leaf| leaf.position.z)
+                .fold(f32::MAX, f32::min);
+            let mut neck = 0;
+            while let [only] = children[neck][..] {
+                if model.segments[only].end.z > lowest_leaf {
+                    break;
+                }
+                neck = only;
+            }
+            // Each coconut leans toward a random leaf, so turning the model turns the bunch.
+            let neck_end = model.segments[neck].end;
+            (0..count)
+                .map(|_| {
+                    let toward = match model.leaves.len() {
+                        0 => Vec3::new(1.0, 0.0, 0.0),
+                        leaves => model.leaves[rng.gen_range(0..leaves)].position - neck_end,
+                    };
+                    let out = Vec3::new(toward.x, toward.y, 0.0).normalized();
+                    let drop = rng.gen_range(0.4..1.2);
+                    attach(neck, out * (size * 1.2) - up * (size * drop))
+                })
+                .collect()
+        }
+        _ => {
+            let mut leaves: Vec<usize> = (0..model.leaves.len()).collect();
+            leaves.shuffle(rng);
+            leaves.truncate(count as usize);
+            let on_face = matches!(kind, AttachmentKind::Blossom | AttachmentKind::Berry);
+            leaves
+                .into_iter()
+                .map(|leaf| {
+                    let offset = if on_face {
+                        model.leaves[leaf].normal * (model.leaves[leaf].size * 0.4)
+                    } else {
+                        up * -(model.leaves[leaf].size * 0.3 + size)
+                    };
+                    attach(leaf, offset)
+                })
+                .collect()
+        }
     }
 }
 
@@ -400,6 +837,7 @@ fn generate_roots(settings: &TreeSettings, rng: &mut StdRng) -> Vec<TreeSegment>
             radius: base_radius,
             normal: Vec3::default(),
             depth: 0,
+            parent: None,
         });
         roots.push(TreeSegment {
             start: mid,
@@ -407,6 +845,7 @@ fn generate_roots(settings: &TreeSettings, rng: &mut StdRng) -> Vec<TreeSegment>
             radius: mid_radius,
             normal: Vec3::default(),
             depth: 0,
+            parent: None,
         });
         roots.push(TreeSegment {
             start: mid2,
@@ -418,6 +857,7 @@ fn generate_roots(settings: &TreeSettings, rng: &mut StdRng) -> Vec<TreeSegment>
             radius: mid2_radius,
             normal: Vec3::default(),
             depth: 0,
+            parent: None,
         });
         roots.push(TreeSegment {
             start: Vec3::new(
@@ -429,6 +869,7 @@ fn generate_roots(settings: &TreeSettings, rng: &mut StdRng) -> Vec<TreeSegment>
             radius: tip_radius,
             normal: Vec3::default(),
             depth: 0,
+            parent: None,
         });
     }
     roots
@@ -468,15 +909,110 @@ fn expand_bounds(point: Vec3, radius: f32, min: &mut Vec3, max: &mut Vec3) {
     max.z = max.z.max(point.z + r);
 }
 
-fn nearest_node(nodes: &[Node], point: Vec3) -> Vec3 {
-    let mut closest = nodes[0].position;
+/// Uniform grid over node indices, filled as nodes are added.
+struct NodeGrid {
+    cell: f32,
+    cells: HashMap<[i32; 3], Vec<usize>>,
+    min: [i32; 3],
+    max: [i32; 3],
+}
+
+impl NodeGrid {
+    fn new(cell: f32) -> Self {
+        Self {
+            cell: cell.max(0.01),
+            cells: HashMap::new(),
+            min: [i32::MAX; 3],
+            max: [i32::MIN; 3],
+        }
+    }
+
+    fn key(&self, point: Vec3) -> [i32; 3] {
+        [
+            (point.x / self.cell).floor() as i32,
+            (point.y / self.cell).floor() as i32,
+            (point.z / self.cell).floor() as i32,
+        ]
+    }
+
+    fn insert(&mut self, index: usize, position: Vec3) {
+        let key = self.key(position);
+        for (axis, coord) in key.iter().enumerate() {
+            self.min[axis] = self.min[axis].min(*coord);
+            self.max[axis] = self.max[axis].max(*coord);
+        }
+        self.cells.entry(key).or_default().push(index);
+    }
+
+    /// Index and distance of the node nearest `point`, breaking ties toward the lower index
+    /// like a scan in insertion order. With a `radius`, only nodes within it are guaranteed
+    /// to be found.
+    fn nearest(&self, nodes: &[Node], point: Vec3, radius: Option<f32>) -> Option<(usize, f32)> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let center = self.key(point);
+        // Rings past the farthest occupied cell hold nothing.
+        let extent = (0..3)
+            .map(|axis| (center[axis] - self.min[axis]).max(self.max[axis] - center[axis]))
+            .max()
+            .unwrap_or(0)
+            .max(0);
+        let last_ring = match radius {
+            Some(radius) => ((radius / self.cell).ceil() as i32).min(extent),
+            None => extent,
+        };
+        let mut best: Option<(usize, f32)> = None;
+        for ring in 0..=last_ring {
+            // Every node outside rings 0..=ring is at least `ring` cells away; stop once the
+            // best is strictly closer, with a margin so float error cannot hide a tie.
+            let bound = (ring - 1) as f32 * self.cell * 0.999;
+            if best.is_some_and(|(_, dist)| ring > 0 && dist < bound) {
+                break;
+            }
+            for key in ring_keys(center, ring) {
+                let Some(indices) = self.cells.get(&key) else {
+                    continue;
+                };
+                for &index in indices {
+                    let dist = (point - nodes[index].position).length();
+                    let closer = match best {
+                        Some((best_index, best_dist)) => {
+                            dist < best_dist || (dist == best_dist && index < best_index)
+                        }
+                        None => true,
+                    };
+                    if closer {
+                        best = Some((index, dist));
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Cell keys on the surface of the cube `ring` cells out from `center`.
+fn ring_keys(center: [i32; 3], ring: i32) -> impl Iterator<Item = [i32; 3]> {
+    (-ring..=ring).flat_map(move |dx| {
+        (-ring..=ring).flat_map(move |dy| {
+            (-ring..=ring).filter_map(move |dz| {
+                (dx.abs() == ring || dy.abs() == ring || dz.abs() == ring)
+                    .then_some([center[0] + dx, center[1] + dy, center[2] + dz])
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+fn nearest_node_brute_force(nodes: &[Node], point: Vec3) -> Option<(usize, f32)> {
+    let mut closest = None;
     let mut closest_dist = f32::MAX;
-    for node in nodes {
-        let delta = point - node.position;
-        let dist = delta.length();
+    for (idx, node) in nodes.iter().enumerate() {
+        let dist = (point - node.position).length();
         if dist < closest_dist {
             closest_dist = dist;
-            closest = node.position;
+            closest = Some((idx, dist));
         }
     }
     closest
@@ -489,16 +1025,18 @@ fn leaf_stem_for_point(
     leaf_size: f32,
     leaf_normal_bias: f32,
     rng: &mut StdRng,
-) -> (Vec3, Vec3, Vec3) {
+) -> (Vec3, Vec3, Vec3, Option<usize>) {
     let base_normal = (position - tree_center).normalized();
     let mut parent_dir = Vec3::new(0.0, 0.0, 1.0);
+    let mut parent = None;
     let mut closest_dist = f32::MAX;
-    for segment in segments {
+    for (index, segment) in segments.iter().enumerate() {
         let delta = position - segment.end;
         let dist = delta.length();
         if dist < closest_dist {
             closest_dist = dist;
             parent_dir = (segment.end - segment.start).normalized();
+            parent = Some(index);
         }
     }
     let mut axis_a = cross(parent_dir, Vec3::new(0.0, 0.0, 1.0));
@@ -513,10 +1051,10 @@ fn leaf_stem_for_point(
     let stem_start = position;
     let stem_end = position + stem_dir * stem_length;
     let blended_normal = (base_normal + stem_dir * leaf_normal_bias).normalized();
-    (stem_start, stem_end, blended_normal)
+    (stem_start, stem_end, blended_normal, parent)
 }
 
-fn cross(a: Vec3, b: Vec3) -> Vec3 {
+pub fn cross(a: Vec3, b: Vec3) -> Vec3 {
     Vec3::new(
         a.y * b.z - a.z * b.y,
         a.z * b.x - a.x * b.z,
@@ -546,4 +1084,144 @@ mod tests {
         assert!(!model.segments.is_empty());
         assert!(!model.leaves.is_empty());
     }
+    #[test]
+    fn cone_crown_narrows_and_lean_tilts_trunk() {
+        let settings = TreeSettings {
+            crown_shape: CrownShape::Cone,
+            lean: 0.3,
+            ..TreeSettings::default()
+        };
+        let model = generate_tree(11, &settings);
+        // The first trunk segment gives the lean per unit of height.
+        let first = model.segments[0].end;
+        let slope = Vec3::new(first.x / first.z, first.y / first.z, 0.0);
+        assert!((slope.length() - settings.lean.tan()).abs() < 1e-4);
+        for leaf in &model.leaves {
+            let offset = leaf.position - slope * leaf.position.z;
+            let t = ((leaf.position.z - settings.trunk_height) / settings.crown_height).max(0.0);
+            let reach = settings.crown_radius * (1.0 - t) + settings.segment_length * 3.0;
+            assert!(offset.x.hypot(offset.y) <= reach);
+        }
+    }
+
+    fn assert_same_tree(a: &TreeModel, b: &TreeModel) {
+        assert_eq!(a.segments.len(), b.segments.len());
+        for (a, b) in a.segments.iter().zip(&b.segments) {
+            assert_eq!((a.start, a.end, a.parent), (b.start, b.end, b.parent));
+        }
+        assert_eq!(a.leaves.len(), b.leaves.len());
+        for (a, b) in a.leaves.iter().zip(&b.leaves) {
+            assert_eq!(a.position, b.position);
+        }
+    }
+
+    #[test]
+    fn grid_lookup_grows_the_same_tree_as_brute_force() {
+        let dense = TreeSettings {
+            attraction_points: 900,
+            crown_shape: CrownShape::Sphere,
+            ..TreeSettings::default()
+        };
+        for (seed, settings) in [(1, TreeSettings::default()), (2, dense.clone()), (3, dense)] {
+            let grid = grow_tree(seed, &settings, Lookup::Grid);
+            let brute = grow_tree(seed, &settings, Lookup::BruteForce);
+            assert_same_tree(&grid, &brute);
+        }
+    }
+
+    /// Run with `cargo test --release dense_tree_growth_benchmark -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn dense_tree_growth_benchmark() {
+        let settings = TreeSettings {
+            crown_radius: 6.0,
+            crown_height: 8.0,
+            attraction_points: 12000,
+            segment_length: 0.25,
+            influence_distance: 1.2,
+            kill_distance: 0.4,
+            max_iterations: 400,
+            max_leaves: 0,
+            ..TreeSettings::default()
+        };
+        let time = |lookup: Lookup| {
+            let start = std::time::Instant::now();
+            let model = grow_tree(9, &settings, lookup);
+            (model, start.elapsed())
+        };
+        let (brute, brute_time) = time(Lookup::BruteForce);
+        let (grid, grid_time) = time(Lookup::Grid);
+        assert_same_tree(&grid, &brute);
+        println!(
+            "{} segments: brute force {brute_time:?}, grid {grid_time:?} ({:.1}x)",
+            grid.segments.len(),
+            brute_time.as_secs_f64() / grid_time.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn attachments_hang_from_leaves_and_sit_in_forks() {
+        let model = generate_tree(4, &TreeSettings::default());
+        let mut rng = StdRng::seed_from_u64(1);
+        let apples = place_attachments(&model, AttachmentKind::Apple, 12, 0.2, &mut rng);
+        assert_eq!(apples.len(), 12);
+        for apple in &apples {
+            let (position, _) = apple.pose(&model);
+            assert!(position.z < model.leaves[apple.anchor].position.z);
+        }
+        let nests = place_attachments(&model, AttachmentKind::Nest, 1000, 0.5, &mut rng);
+        assert!(!nests.is_empty() && nests.len() < 1000);
+        for nest in &nests {
+            let forks = model
+                .segments
+                .iter()
+                .filter(|segment| segment.parent == Some(nest.anchor))
+                .count();
+            assert!(forks >= 2);
+        }
+    }
+
+    #[test]
+    fn four_quarter_turns_bring_the_view_back() {
+        let model = generate_tree(6, &TreeSettings::default());
+        let turned = rotate_tree(&model, 1);
+        for (a, b) in model.leaves.iter().zip(&turned.leaves) {
+            assert_eq!(b.position, Vec3::new(-a.position.y, a.position.x, a.position.z));
+            assert_eq!(b.normal, Vec3::new(-a.normal.y, a.normal.x, a.normal.z));
+        }
+        let back = rotate_tree(&rotate_tree(&turned, 2), 1);
+        assert_same_tree(&back, &model);
+    }
+
+    #[test]
+    fn growth_stages_grow_with_the_same_seed() {
+        let settings = TreeSettings::default();
+        let mature = generate_tree(3, &settings);
+        let sapling = generate_tree(3, &settings.at_growth(0.25, mature.iterations));
+        assert!(sapling.iterations < mature.iterations);
+        assert!(sapling.leaves.len() < mature.leaves.len());
+        let height = |model: &TreeModel| {
+            model.segments.iter().map(|s| s.end.z).fold(f32::MIN, f32::max)
+        };
+        assert!(height(&sapling) < height(&mature));
+    }
+
+    #[test]
+    fn sway_keeps_branches_joined_and_roots_still() {
+        let model = generate_tree(5, &TreeSettings::default());
+        let posed = sway_tree(&model, 0.1, 0.25);
+        for (rest, segment) in model.segments.iter().zip(&posed.segments) {
+            let length = (segment.end - segment.start).length();
+            assert!((length - (rest.end - rest.start).length()).abs() < 1e-4);
+            match segment.parent {
+                Some(parent) => assert_eq!(segment.start, posed.segments[parent].end),
+                None if rest.depth == 0 => assert_eq!(segment.end, rest.end),
+                None => {}
+            }
+        }
+        let tip = posed.leaves.iter().zip(&model.leaves).any(|(posed, rest)| {
+            (posed.position - rest.position).length() > 0.01
+        });
+        assert!(tip);
+    }
 }